@@ -0,0 +1,134 @@
+//! Compares zeitstempel with `std::time::Instant`, quanta and coarsetime on this machine.
+//!
+//! ```text
+//! cargo run --release --example compare
+//! ```
+//!
+//! For every clock it reports the average cost of reading it, the smallest step it was seen
+//! to advance by, and whether it counts time the system spends suspended on this platform.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Reads per latency measurement.
+const CALLS: u32 = 1_000_000;
+
+struct Report {
+    name: &'static str,
+    latency: Duration,
+    resolution: Option<u64>,
+    includes_suspend: &'static str,
+}
+
+/// Measures a clock returning nanoseconds.
+fn measure(name: &'static str, includes_suspend: &'static str, read: impl Fn() -> u64) -> Report {
+    let start = Instant::now();
+    for _ in 0..CALLS {
+        black_box(read());
+    }
+    let latency = start.elapsed() / CALLS;
+
+    // The smallest difference between consecutive distinct readings.
+    let mut resolution = None;
+    let mut previous = read();
+    for _ in 0..CALLS {
+        let now = read();
+        if now > previous {
+            let step = now - previous;
+            resolution = Some(resolution.map_or(step, |r: u64| r.min(step)));
+        }
+        previous = now;
+    }
+
+    Report {
+        name,
+        latency,
+        resolution,
+        includes_suspend,
+    }
+}
+
+fn std_includes_suspend() -> &'static str {
+    if cfg!(any(target_os = "macos", target_os = "ios")) {
+        "no"
+    } else if cfg!(windows) {
+        "yes"
+    } else if cfg!(any(target_os = "linux", target_os = "android")) {
+        // `Instant` uses `CLOCK_MONOTONIC`.
+        "no"
+    } else {
+        "unknown"
+    }
+}
+
+/// quanta and coarsetime use the same clocks as `Instant` or the TSC, which don't include
+/// suspend on Linux and Apple platforms.
+fn others_include_suspend() -> &'static str {
+    if cfg!(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios"
+    )) {
+        "no"
+    } else {
+        "unknown"
+    }
+}
+
+fn main() {
+    let zeitstempel = if cfg!(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        all(windows, not(feature = "forbid-unsafe"))
+    )) {
+        "yes"
+    } else {
+        "like std"
+    };
+    let anchor = Instant::now();
+    let quanta = quanta::Clock::new();
+    let quanta_anchor = quanta.now();
+    let coarsetime_anchor = coarsetime::Instant::now();
+
+    let reports = [
+        measure("zeitstempel::now", zeitstempel, zeitstempel::now),
+        measure(
+            "zeitstempel::now_excluding_suspend",
+            "no",
+            zeitstempel::now_excluding_suspend,
+        ),
+        measure("std::time::Instant", std_includes_suspend(), || {
+            anchor.elapsed().as_nanos() as u64
+        }),
+        // quanta reads the TSC where available, which stops during suspend.
+        measure("quanta::Clock::now", others_include_suspend(), || {
+            quanta.now().duration_since(quanta_anchor).as_nanos() as u64
+        }),
+        measure("coarsetime::Instant::now", others_include_suspend(), || {
+            coarsetime::Instant::now()
+                .duration_since(coarsetime_anchor)
+                .as_nanos()
+        }),
+    ];
+
+    println!(
+        "{:<36} {:>12} {:>16} {:>16}",
+        "clock", "latency", "resolution", "includes suspend"
+    );
+    for report in &reports {
+        let resolution = match report.resolution {
+            Some(step) => format!("{:?}", Duration::from_nanos(step)),
+            None => "-".into(),
+        };
+        println!(
+            "{:<36} {:>12} {:>16} {:>16}",
+            report.name,
+            format!("{:?}", report.latency),
+            resolution,
+            report.includes_suspend
+        );
+    }
+}
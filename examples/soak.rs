@@ -0,0 +1,98 @@
+//! Hammers `zeitstempel::now()` from many threads and checks that time never goes backwards.
+//!
+//! Every thread publishes its latest timestamp to a shared maximum before taking the next one.
+//! A timestamp earlier than a maximum that was already visible when it was taken means the
+//! clock went backwards between threads, e.g. across CPU sockets.
+//!
+//! ```text
+//! cargo run --release --example soak -- [threads] [seconds]
+//! ```
+//!
+//! Defaults to one thread per CPU for 10 seconds.
+
+use std::env;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+struct Report {
+    calls: u64,
+    backwards: u64,
+    largest_step_back: u64,
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let threads = match args.next() {
+        Some(threads) => threads.parse().expect("threads must be a number"),
+        None => thread::available_parallelism().map_or(4, |n| n.get()),
+    };
+    let secs = match args.next() {
+        Some(secs) => secs.parse().expect("seconds must be a number"),
+        None => 10,
+    };
+
+    println!("Calling now() from {} threads for {}s", threads, secs);
+
+    let latest = Arc::new(AtomicU64::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let workers: Vec<_> = (0..threads)
+        .map(|_| {
+            let latest = latest.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let mut report = Report {
+                    calls: 0,
+                    backwards: 0,
+                    largest_step_back: 0,
+                };
+
+                while !stop.load(Ordering::Relaxed) {
+                    let seen = latest.load(Ordering::Acquire);
+                    let now = zeitstempel::now();
+                    if now < seen {
+                        report.backwards += 1;
+                        report.largest_step_back = report.largest_step_back.max(seen - now);
+                    }
+                    latest.fetch_max(now, Ordering::Release);
+                    report.calls += 1;
+                }
+
+                report
+            })
+        })
+        .collect();
+
+    thread::sleep(Duration::from_secs(secs));
+    stop.store(true, Ordering::Relaxed);
+
+    let mut calls = 0;
+    let mut backwards = 0;
+    let mut largest_step_back = 0;
+    for worker in workers {
+        let report = worker.join().unwrap();
+        calls += report.calls;
+        backwards += report.backwards;
+        largest_step_back = largest_step_back.max(report.largest_step_back);
+    }
+
+    println!(
+        "{} calls, {:.1} million per second, {:.1} ns per call and thread",
+        calls,
+        calls as f64 / secs as f64 / 1e6,
+        (secs * threads as u64) as f64 * 1e9 / calls as f64
+    );
+
+    if backwards > 0 {
+        println!(
+            "Time went backwards {} times, by up to {} ns",
+            backwards, largest_step_back
+        );
+        process::exit(1);
+    }
+
+    println!("Time never went backwards");
+}
@@ -1,11 +1,12 @@
 use std::{thread, time::Duration};
 
+use zeitstempel::Instant;
+
 fn main() {
-    let start = zeitstempel::now();
-    println!("Now: {}", start);
+    let start = Instant::now_including_suspend();
+    println!("Now: {}", start.as_timestamp());
 
     thread::sleep(Duration::from_secs(2));
 
-    let diff = zeitstempel::now() - start;
-    println!("Diff: {} ms", Duration::from_nanos(diff).as_millis());
+    println!("Diff: {} ms", start.elapsed().as_millis());
 }
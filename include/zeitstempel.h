@@ -0,0 +1,85 @@
+#ifndef ZEITSTEMPEL_H
+#define ZEITSTEMPEL_H
+
+/* Generated by cbindgen from src/ffi.rs. Don't edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The version of the C ABI.
+ *
+ * It's increased whenever an exported function is removed or changes its signature or
+ * meaning. Adding functions keeps the version, so newer libraries work with older callers.
+ */
+#define ZEITSTEMPEL_ABI_VERSION 1
+
+/**
+ * Describes the clock behind [`zeitstempel_now`], see [`ClockInfo`](crate::ClockInfo).
+ */
+typedef struct ZeitstempelClockInfo {
+  /**
+   * The name of the operating system's clock or function, NUL-terminated.
+   * It's valid until the process exits.
+   */
+  const char *name;
+  /**
+   * The resolution the clock reports for itself, in nanoseconds, or 0 if it doesn't.
+   */
+  uint64_t resolution_ns;
+  /**
+   * Whether time the system spends in sleep or hibernation is counted on this platform.
+   */
+  bool includes_suspend;
+} ZeitstempelClockInfo;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the [`ZEITSTEMPEL_ABI_VERSION`] the library was built with.
+ *
+ * This function is part of every version of the ABI.
+ */
+uint32_t zeitstempel_abi_version(void);
+
+/**
+ * Returns a timestamp corresponding to "now", in nanoseconds, like [`now`](crate::now).
+ *
+ * It includes the time the system spent in suspend where the platform supports it.
+ */
+uint64_t zeitstempel_now(void);
+
+/**
+ * Returns a timestamp corresponding to "now", in nanoseconds, without the time the system spent
+ * in suspend, like [`now_excluding_suspend`](crate::now_excluding_suspend).
+ */
+uint64_t zeitstempel_now_excluding_suspend(void);
+
+/**
+ * Returns [`zeitstempel_now`] as a signed integer, like [`now_i64`](crate::now_i64).
+ *
+ * Timestamps that don't fit saturate to `INT64_MAX`.
+ */
+int64_t zeitstempel_now_i64(void);
+
+/**
+ * Returns [`zeitstempel_now_excluding_suspend`] as a signed integer.
+ *
+ * Timestamps that don't fit saturate to `INT64_MAX`.
+ */
+int64_t zeitstempel_now_excluding_suspend_i64(void);
+
+/**
+ * Describes the clock behind [`zeitstempel_now`], like [`clock_info`](crate::clock_info).
+ */
+struct ZeitstempelClockInfo zeitstempel_clock_info(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ZEITSTEMPEL_H */
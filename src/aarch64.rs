@@ -0,0 +1,123 @@
+//! Timestamp implementation reading the generic timer's virtual counter on AArch64 Linux,
+//! with the `aarch64-counter` feature.
+//!
+//! `CNTVCT_EL0` can be read from userspace without a system call, and `CNTFRQ_EL0` holds its
+//! frequency. It's used if the frequency is set and the kernel uses the counter as its
+//! clocksource as well, where it tells. See [`calibration`](crate::calibration) for how ticks
+//! become `CLOCK_BOOTTIME` nanoseconds.
+//!
+//! Otherwise this falls back to the regular Linux implementation.
+
+use std::arch::asm;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::calibration::Calibrated;
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::linux;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::{bracketed, Readings};
+
+/// The converted counter, `None` if it can't be trusted.
+static COUNTER: Lazy<Option<Calibrated>> = Lazy::new(|| {
+    let frequency = cntfrq();
+    usable(frequency).then(|| Calibrated::new(cntvct, frequency))
+});
+
+/// Reads the virtual counter.
+///
+/// The `isb` keeps the read from being executed ahead of earlier instructions.
+fn cntvct() -> u64 {
+    let ticks: u64;
+    unsafe {
+        asm!("isb", "mrs {}, cntvct_el0", out(reg) ticks, options(nomem, nostack));
+    }
+    ticks
+}
+
+/// Reads the counter frequency in Hz, as set up by the firmware.
+fn cntfrq() -> u64 {
+    let frequency: u64;
+    unsafe {
+        asm!("mrs {}, cntfrq_el0", out(reg) frequency, options(nomem, nostack));
+    }
+    frequency
+}
+
+/// Whether the frequency is set, and the kernel uses the counter as well, where it tells.
+fn usable(frequency: u64) -> bool {
+    let clocksource =
+        std::fs::read_to_string("/sys/devices/system/clocksource/clocksource0/current_clocksource");
+    frequency != 0 && clocksource.map_or(true, |source| source.trim() == "arch_sys_counter")
+}
+
+/// The counter converted to `CLOCK_BOOTTIME` nanoseconds, or `CLOCK_BOOTTIME` itself.
+pub fn now_including_suspend() -> u64 {
+    match &*COUNTER {
+        Some(counter) => counter.now(),
+        None => linux::now_including_suspend(),
+    }
+}
+
+/// Reading the counter can't fail, only the fallback can.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    match &*COUNTER {
+        Some(counter) => Ok(counter.now()),
+        None => linux::try_now_including_suspend(),
+    }
+}
+
+/// `CLOCK_MONOTONIC`, as the counter would need its own pairing for it.
+pub fn now_excluding_suspend() -> u64 {
+    linux::now_excluding_suspend()
+}
+
+/// Reads both clocks back-to-back, or both Linux clocks.
+pub fn now_both() -> Readings {
+    match &*COUNTER {
+        Some(_) => bracketed(now_including_suspend, now_excluding_suspend),
+        None => linux::now_both(),
+    }
+}
+
+/// The converted counter counts nanoseconds.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    match &*COUNTER {
+        Some(counter) => RawTimestamp {
+            value: counter.now(),
+            unit: TickUnit::Nanoseconds,
+        },
+        None => linux::raw_now_including_suspend(),
+    }
+}
+
+/// The converted counter and the fallback both count nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// `CNTVCT_EL0`, with the length of a tick, or `CLOCK_BOOTTIME`.
+pub fn clock_info() -> ClockInfo {
+    match &*COUNTER {
+        Some(counter) => ClockInfo {
+            name: "CNTVCT_EL0",
+            resolution: Some(counter.tick_length().max(Duration::from_nanos(1))),
+            includes_suspend: true,
+        },
+        None => linux::clock_info(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn close_to_boottime() {
+        let counter = now_including_suspend();
+        let boottime = linux::now_including_suspend();
+        assert!(boottime.max(counter) - boottime.min(counter) < 1_000_000);
+    }
+}
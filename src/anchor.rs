@@ -0,0 +1,108 @@
+use std::convert::TryInto;
+use std::time::{Duration, SystemTime};
+
+/// A paired reading of [`now`](crate::now) and the wall clock,
+/// to convert timestamps to approximate wall-clock times and back, e.g. for log correlation.
+///
+/// The conversion assumes the wall clock advanced at the same pace as the timestamps since
+/// the anchor was created. Adjustments of the system time afterwards aren't accounted for:
+/// create a new anchor to pick them up.
+///
+/// ```
+/// use zeitstempel::Anchor;
+///
+/// let anchor = Anchor::new();
+/// let ts = zeitstempel::now();
+/// println!("taken at {:?}", anchor.to_system_time(ts));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Anchor {
+    ts: u64,
+    system: SystemTime,
+    uncertainty: Duration,
+}
+
+impl Anchor {
+    /// Reads both clocks "now".
+    ///
+    /// The wall clock is read between two timestamps, and paired with their midpoint.
+    pub fn new() -> Anchor {
+        let before = crate::now();
+        let system = SystemTime::now();
+        let after = crate::now();
+
+        let gap = after.saturating_sub(before);
+        Anchor {
+            ts: before + gap / 2,
+            system,
+            uncertainty: Duration::from_nanos(gap.div_ceil(2)),
+        }
+    }
+
+    /// Returns how far apart the paired readings can be at most.
+    pub fn uncertainty(&self) -> Duration {
+        self.uncertainty
+    }
+
+    /// Converts a timestamp from [`now`](crate::now) to the approximate wall-clock time it was
+    /// taken at.
+    ///
+    /// Times the platform's [`SystemTime`] can't represent saturate at the anchor's wall-clock
+    /// time.
+    pub fn to_system_time(&self, ts: u64) -> SystemTime {
+        let converted = if ts >= self.ts {
+            self.system.checked_add(Duration::from_nanos(ts - self.ts))
+        } else {
+            self.system.checked_sub(Duration::from_nanos(self.ts - ts))
+        };
+        converted.unwrap_or(self.system)
+    }
+
+    /// Converts a wall-clock time to the approximate timestamp [`now`](crate::now) returned
+    /// at that time.
+    ///
+    /// Saturates at zero for times before the clock started, and at `u64::MAX`.
+    pub fn from_system_time(&self, system: SystemTime) -> u64 {
+        match system.duration_since(self.system) {
+            Ok(later) => {
+                let later = later.as_nanos().try_into().unwrap_or(u64::MAX);
+                self.ts.saturating_add(later)
+            }
+            Err(earlier) => {
+                let earlier = earlier.duration().as_nanos().try_into().unwrap_or(u64::MAX);
+                self.ts.saturating_sub(earlier)
+            }
+        }
+    }
+}
+
+impl Default for Anchor {
+    fn default() -> Anchor {
+        Anchor::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let anchor = Anchor::new();
+        let ts = crate::now();
+        let system = anchor.to_system_time(ts);
+
+        let drift = system
+            .duration_since(SystemTime::now())
+            .unwrap_or_else(|e| e.duration());
+        assert!(drift < Duration::from_secs(1));
+        assert_eq!(ts, anchor.from_system_time(system));
+
+        let earlier = ts.saturating_sub(1_000_000_000);
+        assert_eq!(
+            earlier,
+            anchor.from_system_time(anchor.to_system_time(earlier))
+        );
+        assert_eq!(0, anchor.from_system_time(std::time::UNIX_EPOCH));
+    }
+}
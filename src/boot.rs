@@ -0,0 +1,132 @@
+//! Information about the current boot of the system.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How far the estimated boot time may move before it's considered a different boot.
+///
+/// The estimate moves whenever the wall clock is adjusted, e.g. by time synchronization.
+const BOOT_TIME_TOLERANCE: u64 = 1_000_000_000;
+
+/// Estimates when the clock behind [`now`](crate::now) started, usually at boot,
+/// as nanoseconds since the Unix epoch.
+///
+/// This is derived from the wall clock and moves when the system time is changed.
+/// With the fallback clock it's the start of the process instead.
+pub(crate) fn boot_time() -> u64 {
+    wall_clock_now().saturating_sub(crate::now())
+}
+
+/// Whether two estimates from [`boot_time`] likely belong to the same boot.
+pub(crate) fn same_boot_time(a: u64, b: u64) -> bool {
+    a.max(b) - a.min(b) <= BOOT_TIME_TOLERANCE
+}
+
+/// Identifies a boot: by the operating system's boot ID where there is one,
+/// by the estimated boot time otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Boot {
+    pub(crate) id: Option<u128>,
+    pub(crate) time: u64,
+}
+
+impl Boot {
+    /// The current boot.
+    pub(crate) fn current() -> Boot {
+        Boot {
+            id: boot_id(),
+            time: boot_time(),
+        }
+    }
+
+    /// Whether both were captured during the same boot.
+    pub(crate) fn is_same(&self, other: &Boot) -> bool {
+        match (self.id, other.id) {
+            (Some(a), Some(b)) => a == b,
+            _ => same_boot_time(self.time, other.time),
+        }
+    }
+}
+
+/// The wall-clock time as nanoseconds since the Unix epoch.
+pub(crate) fn wall_clock_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// A unique identifier of the current boot, where the operating system provides one.
+///
+/// On Linux and Android that's the kernel's random `boot_id`,
+/// on macOS and iOS `kern.bootsessionuuid`.
+pub(crate) fn boot_id() -> Option<u128> {
+    cfg_if::cfg_if! {
+        if #[cfg(any(target_os = "linux", target_os = "android"))] {
+            let id = std::fs::read_to_string("/proc/sys/kernel/random/boot_id").ok()?;
+            parse_uuid(&id)
+        } else if #[cfg(all(
+            any(target_os = "macos", target_os = "ios"),
+            not(feature = "forbid-unsafe")
+        ))] {
+            let mut buf = [0u8; 64];
+            let mut len = buf.len();
+            let ret = unsafe {
+                libc::sysctlbyname(
+                    b"kern.bootsessionuuid\0".as_ptr() as *const libc::c_char,
+                    buf.as_mut_ptr() as *mut libc::c_void,
+                    &mut len,
+                    std::ptr::null_mut(),
+                    0,
+                )
+            };
+            if ret != 0 {
+                return None;
+            }
+            let id = std::str::from_utf8(&buf[..len]).ok()?;
+            parse_uuid(id.trim_end_matches('\0'))
+        } else {
+            None
+        }
+    }
+}
+
+/// Parses a UUID in its usual hyphenated hex form.
+#[cfg_attr(
+    not(any(
+        target_os = "linux",
+        target_os = "android",
+        all(
+            any(target_os = "macos", target_os = "ios"),
+            not(feature = "forbid-unsafe")
+        )
+    )),
+    allow(dead_code)
+)]
+fn parse_uuid(uuid: &str) -> Option<u128> {
+    let hex: String = uuid.trim().chars().filter(|&c| c != '-').collect();
+    if hex.len() != 32 {
+        return None;
+    }
+    u128::from_str_radix(&hex, 16).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn uuid() {
+        assert_eq!(
+            Some(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef),
+            parse_uuid("01234567-89ab-cdef-0123-456789abcdef\n")
+        );
+        assert_eq!(None, parse_uuid("01234567-89ab"));
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn stable_boot_id() {
+        assert!(boot_id().is_some());
+        assert_eq!(boot_id(), boot_id());
+    }
+}
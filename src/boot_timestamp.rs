@@ -0,0 +1,101 @@
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::boot::Boot;
+use crate::Timestamp;
+
+/// Two [`BootTimestamp`]s were taken during different boots, so they can't be compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DifferentBoot;
+
+impl fmt::Display for DifferentBoot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the timestamps were taken during different boots")
+    }
+}
+
+impl Error for DifferentBoot {}
+
+/// A [`Timestamp`] together with the boot it was taken in.
+///
+/// Timestamps are only comparable within a single boot.
+/// This one knows its boot and refuses to be compared to a timestamp from another one.
+/// The boot is identified by the operating system's boot ID on Linux, Android, macOS and iOS,
+/// elsewhere by the boot time estimated from the wall clock.
+/// With the `serde` feature it serializes as a record of the timestamp and the boot:
+/// `{"timestamp":123456789,"boot_id":"<32 hex digits>","boot_time":1700000000000000000}`.
+///
+/// ```
+/// use zeitstempel::BootTimestamp;
+///
+/// let start = BootTimestamp::now();
+/// // ...
+/// match BootTimestamp::now().duration_since(&start) {
+///     Ok(elapsed) => println!("took {:?}", elapsed),
+///     Err(e) => println!("can't tell: {}", e),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BootTimestamp {
+    pub(crate) timestamp: Timestamp,
+    pub(crate) boot: Boot,
+}
+
+impl BootTimestamp {
+    /// Returns a timestamp corresponding to "now", in the current boot.
+    pub fn now() -> BootTimestamp {
+        BootTimestamp {
+            timestamp: Timestamp::now(),
+            boot: Boot::current(),
+        }
+    }
+
+    /// Returns the timestamp, without its boot.
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    /// Returns the boot ID the timestamp was taken in, if the operating system provides one.
+    pub fn boot_id(&self) -> Option<u128> {
+        self.boot.id
+    }
+
+    /// Returns whether both timestamps were taken during the same boot.
+    pub fn same_boot(&self, other: &BootTimestamp) -> bool {
+        self.boot.is_same(&other.boot)
+    }
+
+    /// Returns the time elapsed from `earlier` to this timestamp.
+    ///
+    /// Returns a zero duration if `earlier` is later than this timestamp,
+    /// and fails if they were taken during different boots.
+    pub fn duration_since(&self, earlier: &BootTimestamp) -> Result<Duration, DifferentBoot> {
+        if !self.same_boot(earlier) {
+            return Err(DifferentBoot);
+        }
+        Ok(self.timestamp - earlier.timestamp)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rejects_other_boot() {
+        let start = BootTimestamp::now();
+        let end = BootTimestamp::now();
+        assert!(end.same_boot(&start));
+        assert!(end.duration_since(&start).unwrap() < Duration::from_secs(1));
+
+        let other = BootTimestamp {
+            boot: Boot {
+                id: start.boot.id.map(|id| !id),
+                time: start.boot.time - 3600 * 1_000_000_000,
+            },
+            ..start
+        };
+        assert_eq!(Err(DifferentBoot), end.duration_since(&other));
+    }
+}
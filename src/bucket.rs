@@ -0,0 +1,102 @@
+//! Grouping timestamps into fixed-width buckets, e.g. for histograms or time-series windows.
+//!
+//! All functions take timestamps from [`now`](crate::now) and never overflow.
+
+use std::convert::TryInto;
+use std::time::Duration;
+
+/// Rounds `ts` down to a multiple of `bucket`.
+///
+/// A zero-width bucket returns `ts` unchanged.
+/// A bucket wider than the range of timestamps returns `0`.
+///
+/// ```
+/// # use std::time::Duration;
+/// use zeitstempel::bucket::quantize;
+///
+/// assert_eq!(2_000_000_000, quantize(2_345_678_901, Duration::from_secs(1)));
+/// ```
+pub fn quantize(ts: u64, bucket: Duration) -> u64 {
+    match width(bucket) {
+        Some(0) => ts,
+        Some(width) => ts - ts % width,
+        None => 0,
+    }
+}
+
+/// Returns the index of the bucket of `width` that `ts` falls in,
+/// counting buckets from `start`.
+///
+/// Returns `None` if `ts` lies before `start` or `width` is zero.
+///
+/// ```
+/// # use std::time::Duration;
+/// use zeitstempel::bucket::bucket_index;
+///
+/// let start = 1_000;
+/// assert_eq!(Some(0), bucket_index(start, 1_999, Duration::from_nanos(1_000)));
+/// assert_eq!(Some(3), bucket_index(start, 4_000, Duration::from_nanos(1_000)));
+/// assert_eq!(None, bucket_index(start, 999, Duration::from_nanos(1_000)));
+/// ```
+pub fn bucket_index(start: u64, ts: u64, width: Duration) -> Option<u64> {
+    let elapsed = ts.checked_sub(start)?;
+    match self::width(width) {
+        Some(0) => None,
+        Some(width) => Some(elapsed / width),
+        // Wider than any timestamp difference, so everything falls into the first bucket.
+        None => Some(0),
+    }
+}
+
+/// The bucket width in nanoseconds, `None` if it doesn't fit into a timestamp.
+fn width(bucket: Duration) -> Option<u64> {
+    bucket.as_nanos().try_into().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn edges() {
+        assert_eq!(17, quantize(17, Duration::from_nanos(0)));
+        assert_eq!(0, quantize(u64::MAX, Duration::from_secs(u64::MAX)));
+        assert_eq!(u64::MAX - 1, quantize(u64::MAX, Duration::from_nanos(2)));
+
+        assert_eq!(None, bucket_index(0, 10, Duration::from_nanos(0)));
+        assert_eq!(
+            Some(0),
+            bucket_index(0, u64::MAX, Duration::from_secs(u64::MAX))
+        );
+        assert_eq!(
+            Some(u64::MAX),
+            bucket_index(0, u64::MAX, Duration::from_nanos(1))
+        );
+    }
+}
+
+/// Proofs that bucketing never panics, checked with `cargo kani`.
+#[cfg(kani)]
+mod verification {
+    use super::*;
+
+    fn any_duration() -> Duration {
+        Duration::new(kani::any(), kani::any::<u32>() % 1_000_000_000)
+    }
+
+    #[kani::proof]
+    fn quantize_never_panics() {
+        let ts: u64 = kani::any();
+        assert!(quantize(ts, any_duration()) <= ts);
+    }
+
+    #[kani::proof]
+    fn bucket_index_never_panics() {
+        let start: u64 = kani::any();
+        let ts: u64 = kani::any();
+        let index = bucket_index(start, ts, any_duration());
+        if ts < start {
+            assert_eq!(None, index);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+/// Whether time the system spends in suspend consumes a [`Budget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuspendPolicy {
+    /// Suspended time is spent like any other time, as for deadlines seen by a client.
+    Consume,
+    /// Only time the system was running counts, as for CPU-bound work.
+    Exclude,
+}
+
+/// A total allowed duration, spent by the code regions run through it.
+///
+/// Time between regions isn't counted, so a request handler can spend a budget on the
+/// parts under its control and decide what to skip once it runs low.
+///
+/// ```
+/// # use std::time::Duration;
+/// use zeitstempel::{Budget, SuspendPolicy};
+///
+/// let budget = Budget::new(Duration::from_millis(100), SuspendPolicy::Consume);
+/// let parsed = budget.spend(|| "42".parse::<u32>());
+/// if !budget.is_exhausted() {
+///     budget.spend(|| { /* optional enrichment */ });
+/// }
+/// assert!(budget.remaining() <= Duration::from_millis(100));
+/// # drop(parsed);
+/// ```
+#[derive(Debug)]
+pub struct Budget {
+    total: u64,
+    spent: Cell<u64>,
+    policy: SuspendPolicy,
+}
+
+impl Budget {
+    /// Creates a budget of `total`, nothing spent yet.
+    pub fn new(total: Duration, policy: SuspendPolicy) -> Budget {
+        Budget {
+            total: total.as_nanos().min(u128::from(u64::MAX)) as u64,
+            spent: Cell::new(0),
+            policy,
+        }
+    }
+
+    fn now(&self) -> u64 {
+        match self.policy {
+            SuspendPolicy::Consume => crate::now(),
+            SuspendPolicy::Exclude => crate::now_excluding_suspend(),
+        }
+    }
+
+    /// Runs `region`, spending the time it takes.
+    ///
+    /// The region runs even if the budget is exhausted already.
+    /// Regions can be nested, time in a nested region is spent once.
+    pub fn spend<R>(&self, region: impl FnOnce() -> R) -> R {
+        let start = self.now();
+        let spent = self.spent.get();
+        let result = region();
+        let elapsed = self.now().saturating_sub(start);
+        // Nested regions already counted their part: only add what wasn't counted yet.
+        let nested = self.spent.get().saturating_sub(spent);
+        self.spent.set(
+            self.spent
+                .get()
+                .saturating_add(elapsed.saturating_sub(nested)),
+        );
+        result
+    }
+
+    /// Returns the time spent so far.
+    pub fn spent(&self) -> Duration {
+        Duration::from_nanos(self.spent.get())
+    }
+
+    /// Returns the time left, zero once the budget is exhausted.
+    pub fn remaining(&self) -> Duration {
+        Duration::from_nanos(self.total.saturating_sub(self.spent.get()))
+    }
+
+    /// Returns whether all of the budget was spent.
+    pub fn is_exhausted(&self) -> bool {
+        self.spent.get() >= self.total
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn spends_regions_only() {
+        let budget = Budget::new(Duration::from_millis(5), SuspendPolicy::Consume);
+
+        budget.spend(|| thread::sleep(Duration::from_millis(2)));
+        let spent = budget.spent();
+        assert!(spent >= Duration::from_millis(2));
+        assert!(!budget.is_exhausted());
+
+        thread::sleep(Duration::from_millis(5));
+        assert_eq!(spent, budget.spent());
+
+        let outer = crate::Instant::now();
+        budget.spend(|| budget.spend(|| thread::sleep(Duration::from_millis(3))));
+        let outer = outer.elapsed();
+        assert!(budget.is_exhausted());
+        assert_eq!(Duration::from_secs(0), budget.remaining());
+        // The nested region isn't counted twice.
+        assert!(budget.spent() <= spent + outer);
+    }
+}
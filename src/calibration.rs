@@ -0,0 +1,292 @@
+//! Converting a CPU counter to `CLOCK_BOOTTIME` nanoseconds, for the counter backends.
+//!
+//! Counters such as the TSC tick at a fixed frequency, but may stop or reset while the system
+//! is suspended. The conversion is based on a paired reading of the counter and
+//! `CLOCK_BOOTTIME`, which is renewed after [`RESYNC_INTERVAL`] worth of ticks, and picks up
+//! time spent in suspend. For up to the interval after a resume the last suspend isn't counted
+//! yet.
+//!
+//! The reported or measured frequency is never exact. Every renewal also measures the rate
+//! against `CLOCK_BOOTTIME` since the last suspend, and converts the following ticks at that rate.
+//! The first renewals come quickly after another, every [`FIRST_RESYNC`] and twice as long
+//! each time, so the rate is accurate before it's extrapolated over the whole interval.
+//!
+//! Renewing the pairing never moves the converted time backwards. Where the converted time fell
+//! behind `CLOCK_BOOTTIME`, it jumps forward to catch up. Where it ran ahead, the following
+//! intervals are converted slightly slower until the difference is made up.
+
+use std::sync::atomic::{fence, AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::linux;
+
+/// How often the pairing with `CLOCK_BOOTTIME` is renewed.
+const RESYNC_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How soon the pairing is renewed first.
+const FIRST_RESYNC: Duration = Duration::from_millis(1);
+
+/// Running ahead, the conversion is slowed down by at most a 1/`MAX_SLEW`th, i.e. 500 ppm.
+const MAX_SLEW: u64 = 2_000;
+
+/// A difference to `CLOCK_BOOTTIME` larger than this at a renewal is taken as a suspend, or a
+/// reset of the counter, so the rate isn't measured across it.
+const MAX_DRIFT: u64 = 1_000_000;
+
+/// How long the frequency is measured for, if the hardware doesn't report it.
+const MEASURE_WINDOW: Duration = Duration::from_millis(10);
+
+const NS_PER_S: u128 = 1_000_000_000;
+
+/// A counter converted to `CLOCK_BOOTTIME` nanoseconds.
+pub(crate) struct Calibrated {
+    read: fn() -> u64,
+    /// Nanoseconds per tick from the nominal frequency, as a 32.32 fixed-point number.
+    nominal: u64,
+    /// [`RESYNC_INTERVAL`] in ticks.
+    max_interval: u64,
+    /// Ticks until the next renewal, from [`FIRST_RESYNC`] up to `max_interval`.
+    interval: AtomicU64,
+    /// A sequence lock: odd while the pairing is being renewed.
+    seq: AtomicU64,
+    base_ticks: AtomicU64,
+    base_nanos: AtomicU64,
+    /// Nanoseconds per tick from the base on, like `nominal`.
+    mult: AtomicU64,
+    /// The pairing the rate is measured from, renewed after a suspend, and the last measured
+    /// rate, without the slew of `mult`. Only used while renewing.
+    anchor_ticks: AtomicU64,
+    anchor_nanos: AtomicU64,
+    rate: AtomicU64,
+    renewing: AtomicBool,
+}
+
+impl Calibrated {
+    /// Sets up the conversion for a counter ticking `frequency` times a second.
+    pub(crate) fn new(read: fn() -> u64, frequency: u64) -> Calibrated {
+        let frequency = u128::from(frequency.max(1));
+        let nominal = ((NS_PER_S << 32) / frequency) as u64;
+        let calibrated = Calibrated {
+            read,
+            nominal,
+            max_interval: (frequency * RESYNC_INTERVAL.as_nanos() / NS_PER_S) as u64,
+            interval: AtomicU64::new((frequency * FIRST_RESYNC.as_nanos() / NS_PER_S) as u64),
+            seq: AtomicU64::new(0),
+            base_ticks: AtomicU64::new(0),
+            base_nanos: AtomicU64::new(0),
+            mult: AtomicU64::new(nominal),
+            anchor_ticks: AtomicU64::new(0),
+            anchor_nanos: AtomicU64::new(0),
+            rate: AtomicU64::new(nominal),
+            renewing: AtomicBool::new(false),
+        };
+        calibrated.resync();
+        calibrated
+    }
+
+    /// Returns the nanoseconds per tick, rounded.
+    pub(crate) fn tick_length(&self) -> Duration {
+        Duration::from_nanos(((u128::from(self.nominal) + (1 << 31)) >> 32) as u64)
+    }
+
+    /// Reads the counter and converts it to `CLOCK_BOOTTIME` nanoseconds.
+    pub(crate) fn now(&self) -> u64 {
+        let (base_ticks, base_nanos, mult) = self.base();
+        let ticks = (self.read)();
+
+        if ticks < base_ticks || ticks - base_ticks >= self.interval.load(Ordering::Relaxed) {
+            if let Some(nanos) = self.resync() {
+                return nanos;
+            }
+            // Another thread is renewing the pairing.
+            if ticks < base_ticks {
+                return linux::now_including_suspend();
+            }
+        }
+
+        base_nanos.saturating_add(to_nanos(ticks - base_ticks, mult))
+    }
+
+    /// Reads the pairing and its rate, retrying while it's being renewed.
+    fn base(&self) -> (u64, u64, u64) {
+        loop {
+            let seq = self.seq.load(Ordering::Acquire);
+            if seq % 2 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+
+            let ticks = self.base_ticks.load(Ordering::Relaxed);
+            let nanos = self.base_nanos.load(Ordering::Relaxed);
+            let mult = self.mult.load(Ordering::Relaxed);
+            fence(Ordering::Acquire);
+            if self.seq.load(Ordering::Relaxed) == seq {
+                return (ticks, nanos, mult);
+            }
+        }
+    }
+
+    /// Pairs a fresh counter reading with `CLOCK_BOOTTIME`, and returns the converted time.
+    ///
+    /// Returns `None` if another thread is doing so already.
+    fn resync(&self) -> Option<u64> {
+        if self
+            .renewing
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return None;
+        }
+
+        let (old_ticks, old_nanos, old_mult) = self.base();
+        // Pairing with the counter's reading after the clock was read errs on the side of
+        // being behind `CLOCK_BOOTTIME`, which the next renewal catches up with.
+        let (_, ticks, boottime) = bracketed_reading(self.read, linux::now_including_suspend);
+        let extrapolated = match ticks.checked_sub(old_ticks) {
+            Some(elapsed) if old_nanos > 0 => old_nanos.saturating_add(to_nanos(elapsed, old_mult)),
+            _ => 0,
+        };
+        let nanos = boottime.max(extrapolated);
+        let mult = self.measure_rate(ticks, boottime, extrapolated);
+
+        // Running ahead by `ahead`, the next interval is converted that much slower,
+        // by at most `MAX_SLEW`, so durations measured meanwhile stay accurate.
+        let ahead = extrapolated.saturating_sub(boottime);
+        let next = self.interval.load(Ordering::Relaxed);
+        self.interval
+            .store((next * 2).min(self.max_interval), Ordering::Relaxed);
+        let interval = to_nanos(next, mult).max(1);
+        let slewed = u128::from(mult) * u128::from(interval - ahead.min(interval / MAX_SLEW))
+            / u128::from(interval);
+
+        self.seq.fetch_add(1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        self.base_ticks.store(ticks, Ordering::Relaxed);
+        self.base_nanos.store(nanos, Ordering::Relaxed);
+        self.mult.store(slewed as u64, Ordering::Relaxed);
+        self.seq.fetch_add(1, Ordering::Release);
+
+        self.renewing.store(false, Ordering::Release);
+        Some(nanos)
+    }
+
+    /// Returns the nanoseconds per tick measured since the anchor, like `nominal`.
+    ///
+    /// Starts over from this pairing if the counter went backwards or was too far off
+    /// `CLOCK_BOOTTIME`, e.g. after a suspend. The rate may differ from the nominal one by
+    /// at most 1%, so a misread clock doesn't throw it off.
+    fn measure_rate(&self, ticks: u64, boottime: u64, extrapolated: u64) -> u64 {
+        let anchor_ticks = self.anchor_ticks.load(Ordering::Relaxed);
+        let anchor_nanos = self.anchor_nanos.load(Ordering::Relaxed);
+        let drift = boottime.max(extrapolated) - boottime.min(extrapolated);
+
+        if anchor_nanos == 0 || ticks <= anchor_ticks || drift > MAX_DRIFT {
+            self.anchor_ticks.store(ticks, Ordering::Relaxed);
+            self.anchor_nanos.store(boottime, Ordering::Relaxed);
+            return self.rate.load(Ordering::Relaxed);
+        }
+
+        let nanos = u128::from(boottime.saturating_sub(anchor_nanos));
+        let measured = (nanos << 32) / u128::from(ticks - anchor_ticks);
+        let nominal = u128::from(self.nominal);
+        let rate = measured.clamp(nominal * 99 / 100, nominal * 101 / 100) as u64;
+        self.rate.store(rate, Ordering::Relaxed);
+        rate
+    }
+}
+
+/// Converts `ticks` at `mult` nanoseconds per tick, a 32.32 fixed-point number.
+fn to_nanos(ticks: u64, mult: u64) -> u64 {
+    let nanos = (u128::from(ticks) * u128::from(mult)) >> 32;
+    nanos.min(u128::from(u64::MAX)) as u64
+}
+
+/// Measures how often `read` ticks per second, against `CLOCK_MONOTONIC`,
+/// for counters that don't report their frequency.
+///
+/// Blocks the calling thread for about 10 milliseconds.
+#[cfg_attr(not(target_arch = "x86_64"), allow(dead_code))]
+pub(crate) fn measure_frequency(read: fn() -> u64) -> u64 {
+    let (start_ticks, start_nanos) = paired_reading(read, linux::now_excluding_suspend);
+    thread::sleep(MEASURE_WINDOW);
+    let (end_ticks, end_nanos) = paired_reading(read, linux::now_excluding_suspend);
+
+    let ticks = u128::from(end_ticks.saturating_sub(start_ticks));
+    let nanos = u128::from(end_nanos.saturating_sub(start_nanos)).max(1);
+    (ticks * NS_PER_S / nanos) as u64
+}
+
+/// Reads the counter before and after `clock`, keeping the tightest of a few attempts.
+fn bracketed_reading(read: fn() -> u64, clock: fn() -> u64) -> (u64, u64, u64) {
+    (0..5)
+        .map(|_| {
+            let before = read();
+            let nanos = clock();
+            let after = read().max(before);
+            (before, after, nanos)
+        })
+        .min_by_key(|&(before, after, _)| after - before)
+        .unwrap_or_default()
+}
+
+/// Pairs the clock's reading with the midpoint of the counter's.
+fn paired_reading(read: fn() -> u64, clock: fn() -> u64) -> (u64, u64) {
+    let (before, after, nanos) = bracketed_reading(read, clock);
+    (before + (after - before) / 2, nanos)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn nanos() -> u64 {
+        linux::now_excluding_suspend()
+    }
+
+    #[test]
+    fn follows_boottime() {
+        let calibrated = Calibrated::new(nanos, 1_000_000_000);
+        assert_eq!(Duration::from_nanos(1), calibrated.tick_length());
+
+        let mut last = 0;
+        for _ in 0..1_000 {
+            let now = calibrated.now();
+            assert!(now >= last);
+            last = now;
+        }
+
+        let boottime = linux::now_including_suspend();
+        assert!(boottime.max(last) - boottime.min(last) < 1_000_000);
+    }
+
+    /// A counter running 0.1% faster than its nominal frequency of 1 GHz.
+    fn fast_nanos() -> u64 {
+        linux::now_including_suspend() / 1_000 * 1_001
+    }
+
+    #[test]
+    fn corrects_rate() {
+        let calibrated = Calibrated::new(fast_nanos, 1_000_000_000);
+
+        let mut last = 0;
+        let mut off = 0;
+        for _ in 0..1_500 {
+            let now = calibrated.now();
+            assert!(now >= last);
+            last = now;
+            let boottime = linux::now_including_suspend();
+            off = boottime.max(now) - boottime.min(now);
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        // Uncorrected it'd be about 1.5 ms off by now.
+        assert!(off < 100_000, "{} ns off", off);
+    }
+
+    #[test]
+    fn measures_frequency() {
+        let frequency = measure_frequency(nanos);
+        assert!((990_000_000..1_010_000_000).contains(&frequency));
+    }
+}
@@ -0,0 +1,114 @@
+//! Channels stamping every message with its send time, to measure queue latency.
+//!
+//! Send times come from [`now`](crate::now), so the queue delay of a message that sat in the
+//! channel while the system was suspended includes the suspended time.
+
+use std::fmt;
+use std::sync::mpsc::{self, RecvError, RecvTimeoutError, SendError, TryRecvError};
+use std::time::Duration;
+
+use crate::Timestamp;
+
+/// Creates a channel whose messages carry their send time, like [`mpsc::channel`].
+///
+/// ```
+/// let (tx, rx) = zeitstempel::timestamped_channel();
+/// tx.send("hello").unwrap();
+///
+/// let (message, sent, queue_delay) = rx.recv().unwrap();
+/// assert_eq!("hello", message);
+/// # drop((sent, queue_delay));
+/// ```
+pub fn timestamped_channel<T>() -> (Sender<T>, Receiver<T>) {
+    let (tx, rx) = mpsc::channel();
+    (Sender(tx), Receiver(rx))
+}
+
+/// The sending half of a [`timestamped_channel`]. Can be cloned to send from many threads.
+pub struct Sender<T>(mpsc::Sender<(T, Timestamp)>);
+
+impl<T> Sender<T> {
+    /// Sends a message, stamped with the current time.
+    ///
+    /// Fails, returning the message, if the receiver was dropped.
+    pub fn send(&self, message: T) -> Result<(), SendError<T>> {
+        self.0
+            .send((message, Timestamp(crate::now())))
+            .map_err(|SendError((message, _))| SendError(message))
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        Sender(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+/// The receiving half of a [`timestamped_channel`].
+///
+/// Every message comes with its send time and the time it spent queued.
+pub struct Receiver<T>(mpsc::Receiver<(T, Timestamp)>);
+
+impl<T> Receiver<T> {
+    /// Blocks until a message arrives, see [`mpsc::Receiver::recv`].
+    pub fn recv(&self) -> Result<(T, Timestamp, Duration), RecvError> {
+        self.0.recv().map(delayed)
+    }
+
+    /// Returns a message if one is queued, see [`mpsc::Receiver::try_recv`].
+    pub fn try_recv(&self) -> Result<(T, Timestamp, Duration), TryRecvError> {
+        self.0.try_recv().map(delayed)
+    }
+
+    /// Blocks for at most `timeout` until a message arrives,
+    /// see [`mpsc::Receiver::recv_timeout`].
+    ///
+    /// The timeout is measured by the standard library and might not include suspend time.
+    pub fn recv_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<(T, Timestamp, Duration), RecvTimeoutError> {
+        self.0.recv_timeout(timeout).map(delayed)
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+/// Adds the queue delay to a received message.
+fn delayed<T>((message, sent): (T, Timestamp)) -> (T, Timestamp, Duration) {
+    let delay = Duration::from_nanos(crate::now().saturating_sub(sent.0));
+    (message, sent, delay)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn measures_queue_delay() {
+        let (tx, rx) = timestamped_channel();
+        let before = crate::now();
+        tx.send(1).unwrap();
+        thread::sleep(Duration::from_millis(2));
+
+        let (message, sent, delay) = rx.recv().unwrap();
+        assert_eq!(1, message);
+        assert!(sent.0 >= before);
+        assert!(delay >= Duration::from_millis(2));
+        assert_eq!(Err(TryRecvError::Empty), rx.try_recv().map(|(m, _, _)| m));
+
+        drop(rx);
+        assert_eq!(SendError(2), tx.send(2).unwrap_err());
+    }
+}
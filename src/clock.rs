@@ -0,0 +1,127 @@
+//! A trait to pass clocks around, for code that wants its time source substituted in tests.
+
+use std::convert::TryInto;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A source of timestamps in nanoseconds, like [`now`](crate::now).
+///
+/// Accept `impl Clock` instead of calling [`now`](crate::now) directly, and callers can
+/// substitute the time source without global state: [`SystemClock`] in production,
+/// a [`ManualClock`] in tests and simulations.
+///
+/// ```
+/// use std::time::Duration;
+/// use zeitstempel::{Clock, ManualClock};
+///
+/// struct Lease<C> {
+///     clock: C,
+///     expires: u64,
+/// }
+///
+/// impl<C: Clock> Lease<C> {
+///     fn is_expired(&self) -> bool {
+///         self.clock.now() >= self.expires
+///     }
+/// }
+///
+/// let clock = ManualClock::new(0);
+/// let lease = Lease { clock: &clock, expires: 1_000 };
+/// assert!(!lease.is_expired());
+///
+/// clock.advance(Duration::from_micros(1));
+/// assert!(lease.is_expired());
+/// ```
+pub trait Clock {
+    /// Returns the current timestamp in nanoseconds.
+    fn now(&self) -> u64;
+}
+
+/// The system clock behind [`now`](crate::now), including suspend time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        crate::now()
+    }
+}
+
+/// A clock that only moves when told to.
+///
+/// It can be shared by reference or in an [`Arc`] and moved from another thread.
+#[derive(Debug, Default)]
+pub struct ManualClock(AtomicU64);
+
+impl ManualClock {
+    /// Creates a clock reading `nanos`.
+    pub const fn new(nanos: u64) -> ManualClock {
+        ManualClock(AtomicU64::new(nanos))
+    }
+
+    /// Sets the clock to `nanos`. The clock may be set backwards.
+    pub fn set(&self, nanos: u64) {
+        self.0.store(nanos, Ordering::Relaxed);
+    }
+
+    /// Moves the clock forward by `duration`, saturating at `u64::MAX`.
+    pub fn advance(&self, duration: Duration) {
+        let nanos = duration.as_nanos().try_into().unwrap_or(u64::MAX);
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |now| {
+                Some(now.saturating_add(nanos))
+            });
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> u64 {
+        (**self).now()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> u64 {
+        (**self).now()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Box<C> {
+    fn now(&self) -> u64 {
+        (**self).now()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn manual() {
+        let clock = ManualClock::new(10);
+        assert_eq!(10, clock.now());
+
+        clock.advance(Duration::from_nanos(5));
+        assert_eq!(15, clock.now());
+
+        clock.set(u64::MAX - 1);
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(u64::MAX, clock.now());
+    }
+
+    #[test]
+    fn system() {
+        let before = crate::now();
+        let clocks: [Box<dyn Clock>; 2] = [Box::new(SystemClock), Box::new(ManualClock::new(0))];
+        assert!(clocks[0].now() >= before);
+        assert_eq!(0, clocks[1].now());
+    }
+}
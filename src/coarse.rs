@@ -0,0 +1,77 @@
+//! A cheaper, coarser read of the suspend-including clock, with the `coarse` feature.
+
+cfg_if::cfg_if! {
+    if #[cfg(any(target_os = "linux", target_os = "android"))] {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        use crate::guard::Latest;
+        use crate::linux;
+
+        /// How often the suspend offset is refreshed, in nanoseconds of the coarse clock.
+        const REFRESH_INTERVAL: u64 = 100_000_000;
+
+        /// How far `CLOCK_MONOTONIC_COARSE` is behind `CLOCK_BOOTTIME`.
+        /// It only grows, with every suspend.
+        static OFFSET: Latest = Latest::new();
+
+        /// The coarse reading at the last refresh of [`OFFSET`].
+        static REFRESHED: AtomicU64 = AtomicU64::new(0);
+
+        fn read() -> u64 {
+            let coarse = linux::clock_gettime(libc::CLOCK_MONOTONIC_COARSE);
+            let refreshed = REFRESHED.load(Ordering::Relaxed);
+            if refreshed == 0 || coarse.saturating_sub(refreshed) >= REFRESH_INTERVAL {
+                REFRESHED.store(coarse, Ordering::Relaxed);
+                OFFSET.advance(linux::offset_to(libc::CLOCK_MONOTONIC_COARSE));
+            }
+
+            coarse + OFFSET.get()
+        }
+    } else if #[cfg(all(any(target_os = "macos", target_os = "ios"), not(feature = "forbid-unsafe")))] {
+        fn read() -> u64 {
+            crate::mac::coarse_now_including_suspend()
+        }
+    } else if #[cfg(all(windows, not(feature = "forbid-unsafe")))] {
+        fn read() -> u64 {
+            crate::win::coarse_now_including_suspend()
+        }
+    } else {
+        fn read() -> u64 {
+            crate::now()
+        }
+    }
+}
+
+/// Returns a timestamp corresponding to "now", cheaper to read but coarser than [`now`](crate::now).
+///
+/// It's the same clock as [`now`](crate::now), with a resolution of about 1 to 4 milliseconds,
+/// for hot loops where the cost of reading the precise clock shows up:
+///
+/// * On Linux and Android it's `CLOCK_MONOTONIC_COARSE`, which the vDSO reads without a
+///   system call, plus the time spent in suspend.
+///   That's refreshed every 100 ms of the coarse clock, so for up to 100 ms after a resume
+///   the last suspend isn't counted yet.
+/// * On macOS and iOS it's `mach_continuous_approximate_time`.
+/// * On Windows it's the interrupt time, which only advances with the timer interrupt,
+///   also when [`now`](crate::now) reads the precise interrupt time.
+/// * Elsewhere it's [`now`](crate::now).
+///
+/// Timestamps from this and [`now`](crate::now) can be compared, within the coarse resolution.
+pub fn coarse() -> u64 {
+    read()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn close_to_now() {
+        let first = coarse();
+        let precise = crate::now();
+        let second = coarse();
+
+        assert!(first <= second);
+        assert!(precise.max(second) - precise.min(second) < 20_000_000);
+    }
+}
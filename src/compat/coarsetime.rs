@@ -0,0 +1,87 @@
+//! A stand-in for [`coarsetime::Instant`](https://docs.rs/coarsetime/latest/coarsetime/struct.Instant.html).
+//!
+//! ```
+//! use zeitstempel::compat::coarsetime::Instant;
+//!
+//! let start = Instant::now();
+//! Instant::update();
+//! let elapsed = start.elapsed_since_recent();
+//! # drop(elapsed);
+//! ```
+
+use std::convert::TryInto;
+use std::ops::{Add, Sub};
+use std::time::Duration;
+
+use crate::guard::Latest;
+
+/// The time stored by the last call to [`Instant::update`], `0` before the first.
+static RECENT: Latest = Latest::new();
+
+/// A measurement of the suspend-including clock, mirroring `coarsetime::Instant`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant(u64);
+
+impl Instant {
+    /// Returns an instant corresponding to "now".
+    pub fn now() -> Instant {
+        Instant(crate::now())
+    }
+
+    /// Returns the instant stored by the last call to [`Instant::update`].
+    ///
+    /// Before the first update this reads the clock instead.
+    pub fn recent() -> Instant {
+        match RECENT.get() {
+            0 => Instant::now(),
+            recent => Instant(recent),
+        }
+    }
+
+    /// Stores "now" to be returned by [`Instant::recent`].
+    pub fn update() {
+        RECENT.advance(crate::now());
+    }
+
+    /// Returns the time elapsed since this instant.
+    pub fn elapsed(&self) -> Duration {
+        Instant::now().duration_since(*self)
+    }
+
+    /// Returns the time elapsed between this instant and [`Instant::recent`].
+    pub fn elapsed_since_recent(&self) -> Duration {
+        Instant::recent().duration_since(*self)
+    }
+
+    /// Returns the time elapsed from `earlier` to this instant, zero if `earlier` is later.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        Duration::from_nanos(self.0.saturating_sub(earlier.0))
+    }
+
+    /// Returns the raw clock value, in nanoseconds.
+    pub fn as_ticks(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the raw clock value, in nanoseconds.
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    fn sub(self, other: Instant) -> Duration {
+        self.duration_since(other)
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, duration: Duration) -> Instant {
+        let duration = duration.as_nanos().try_into().unwrap_or(u64::MAX);
+        Instant(self.0.saturating_add(duration))
+    }
+}
@@ -0,0 +1,123 @@
+//! A stand-in for [`minstant::Instant`](https://docs.rs/minstant/latest/minstant/struct.Instant.html).
+//!
+//! ```
+//! use zeitstempel::compat::minstant::{Anchor, Instant};
+//!
+//! let anchor = Anchor::new();
+//! let start = Instant::now();
+//! println!("started at {}ns since the epoch", start.as_unix_nanos(&anchor));
+//! ```
+
+use std::convert::TryInto;
+use std::ops::{Add, Sub};
+use std::time::Duration;
+
+use crate::boot;
+
+/// Always `false`: this clock never uses the TSC.
+pub fn is_tsc_available() -> bool {
+    false
+}
+
+/// A measurement of the suspend-including clock, mirroring `minstant::Instant`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant(u64);
+
+impl Instant {
+    /// Returns an instant corresponding to "now".
+    pub fn now() -> Instant {
+        Instant(crate::now())
+    }
+
+    /// Returns the time elapsed since this instant.
+    pub fn elapsed(&self) -> Duration {
+        Instant::now().saturating_duration_since(*self)
+    }
+
+    /// Returns the time elapsed from `earlier` to this instant, zero if `earlier` is later.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        self.saturating_duration_since(earlier)
+    }
+
+    /// Returns the time elapsed from `earlier` to this instant, `None` if `earlier` is later.
+    pub fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
+        self.0.checked_sub(earlier.0).map(Duration::from_nanos)
+    }
+
+    /// Returns the time elapsed from `earlier` to this instant, zero if `earlier` is later.
+    pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or_default()
+    }
+
+    /// Returns this instant plus `duration`, `None` on overflow.
+    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        let duration: u64 = duration.as_nanos().try_into().ok()?;
+        self.0.checked_add(duration).map(Instant)
+    }
+
+    /// Returns this instant minus `duration`, `None` on underflow.
+    pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+        let duration: u64 = duration.as_nanos().try_into().ok()?;
+        self.0.checked_sub(duration).map(Instant)
+    }
+
+    /// Converts this instant to nanoseconds since the Unix epoch, using `anchor`.
+    pub fn as_unix_nanos(&self, anchor: &Anchor) -> u64 {
+        if self.0 >= anchor.ts {
+            anchor.unix_nanos.saturating_add(self.0 - anchor.ts)
+        } else {
+            anchor.unix_nanos.saturating_sub(anchor.ts - self.0)
+        }
+    }
+}
+
+/// A pair of clock and wall-clock readings, mirroring `minstant::Anchor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Anchor {
+    ts: u64,
+    unix_nanos: u64,
+}
+
+impl Anchor {
+    /// Reads both clocks "now".
+    pub fn new() -> Anchor {
+        Anchor {
+            ts: crate::now(),
+            unix_nanos: boot::wall_clock_now(),
+        }
+    }
+}
+
+impl Default for Anchor {
+    fn default() -> Anchor {
+        Anchor::new()
+    }
+}
+
+/// Panics on overflow, like `minstant`.
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, duration: Duration) -> Instant {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to instant")
+    }
+}
+
+/// Panics on underflow, like `minstant`.
+impl Sub<Duration> for Instant {
+    type Output = Instant;
+
+    fn sub(self, duration: Duration) -> Instant {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from instant")
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    fn sub(self, other: Instant) -> Duration {
+        self.duration_since(other)
+    }
+}
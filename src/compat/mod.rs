@@ -0,0 +1,9 @@
+//! Drop-in replacements mirroring other crates' timestamp types,
+//! available with the `compat` feature.
+//!
+//! They ease migrating code written against those crates to the suspend-including clock:
+//! switch the import, keep the calls.
+//! Durations are returned as [`std::time::Duration`].
+
+pub mod coarsetime;
+pub mod minstant;
@@ -0,0 +1,79 @@
+//! Durations from configuration files and command lines, with the `humantime` feature.
+//!
+//! The scheduler and the other helpers taking a [`Duration`] can be driven by strings like
+//! `"90s"` or `"2h 30m"`:
+//!
+//! ```
+//! use zeitstempel::config::parse_duration;
+//! use zeitstempel::{CatchUp, Scheduler};
+//!
+//! let interval = parse_duration("2h30m").unwrap();
+//! let scheduler = Scheduler::new();
+//! scheduler.schedule_every(interval, CatchUp::RunOnce, || println!("backup"));
+//! ```
+//!
+//! The format is that of [humantime](https://docs.rs/humantime).
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub use humantime::DurationError;
+
+/// Parses a duration like `"90s"`, `"2h30m"` or `"1day 12h"`.
+pub fn parse_duration(s: &str) -> Result<Duration, DurationError> {
+    humantime::parse_duration(s)
+}
+
+/// A duration read from configuration.
+///
+/// Parses with [`FromStr`] and displays in the same format, so it fits into configuration
+/// structs and command line parsers.
+///
+/// ```
+/// use zeitstempel::config::ConfigDuration;
+///
+/// let timeout: ConfigDuration = "1m 30s".parse().unwrap();
+/// assert_eq!(90, timeout.0.as_secs());
+/// assert_eq!("1m 30s", timeout.to_string());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConfigDuration(pub Duration);
+
+impl FromStr for ConfigDuration {
+    type Err = DurationError;
+
+    fn from_str(s: &str) -> Result<ConfigDuration, DurationError> {
+        parse_duration(s).map(ConfigDuration)
+    }
+}
+
+impl fmt::Display for ConfigDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&humantime::format_duration(self.0), f)
+    }
+}
+
+impl From<ConfigDuration> for Duration {
+    fn from(duration: ConfigDuration) -> Duration {
+        duration.0
+    }
+}
+
+impl From<Duration> for ConfigDuration {
+    fn from(duration: Duration) -> ConfigDuration {
+        ConfigDuration(duration)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses() {
+        assert_eq!(Duration::from_secs(90), parse_duration("90s").unwrap());
+        assert_eq!(Duration::from_secs(9_000), parse_duration("2h30m").unwrap());
+        assert!(parse_duration("soon").is_err());
+    }
+}
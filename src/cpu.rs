@@ -0,0 +1,279 @@
+//! CPU time consumed by the current thread, the process and its children.
+
+use std::time::Duration;
+
+/// Returns the CPU time consumed by the current thread, user and system time combined.
+///
+/// `None` where the operating system doesn't provide it.
+pub fn cpu_time_thread() -> Option<Duration> {
+    sys::thread()
+}
+
+/// Returns the CPU time consumed by the current process, all of its threads included.
+///
+/// Comparing it to a duration from [`now`](crate::now) shows how much of the elapsed time
+/// was spent computing. CPU time doesn't advance while the system is suspended.
+///
+/// `None` where the operating system doesn't provide it.
+pub fn cpu_time_process() -> Option<Duration> {
+    sys::process()
+}
+
+/// Returns the CPU time consumed by the process's children.
+///
+/// * On Unix, these are the terminated children that have been waited for, and their
+///   waited-for descendants, as reported by `getrusage(RUSAGE_CHILDREN)`.
+/// * On Windows, where processes don't keep track of their children, these are the other
+///   processes in the job object the current process belongs to. That's only `Some` if the
+///   process runs in a job, e.g. one its launcher created for the process tree.
+pub fn cpu_time_children() -> Option<Duration> {
+    sys::children()
+}
+
+#[cfg(all(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios"
+    ),
+    not(feature = "forbid-unsafe")
+))]
+mod sys {
+    use std::mem::MaybeUninit;
+    use std::time::Duration;
+
+    fn clock(clock: libc::clockid_t) -> Option<Duration> {
+        let mut ts = MaybeUninit::uninit();
+        if unsafe { libc::clock_gettime(clock, ts.as_mut_ptr()) } != 0 {
+            return None;
+        }
+        let ts = unsafe { ts.assume_init() };
+        Some(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+    }
+
+    fn timeval(tv: libc::timeval) -> Duration {
+        Duration::new(tv.tv_sec as u64, tv.tv_usec as u32 * 1_000)
+    }
+
+    pub(super) fn thread() -> Option<Duration> {
+        clock(libc::CLOCK_THREAD_CPUTIME_ID)
+    }
+
+    pub(super) fn process() -> Option<Duration> {
+        clock(libc::CLOCK_PROCESS_CPUTIME_ID)
+    }
+
+    pub(super) fn children() -> Option<Duration> {
+        let mut usage = MaybeUninit::uninit();
+        if unsafe { libc::getrusage(libc::RUSAGE_CHILDREN, usage.as_mut_ptr()) } != 0 {
+            return None;
+        }
+        let usage = unsafe { usage.assume_init() };
+        Some(timeval(usage.ru_utime) + timeval(usage.ru_stime))
+    }
+}
+
+/// Safe implementation with rustix and procfs, for the `forbid-unsafe` feature.
+#[cfg(all(
+    any(target_os = "linux", target_os = "android"),
+    feature = "forbid-unsafe"
+))]
+mod sys {
+    use std::time::Duration;
+
+    use rustix::time::{clock_gettime, ClockId};
+
+    fn clock(clock: ClockId) -> Option<Duration> {
+        let ts = clock_gettime(clock);
+        Some(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+    }
+
+    pub(super) fn thread() -> Option<Duration> {
+        clock(ClockId::ThreadCPUTime)
+    }
+
+    pub(super) fn process() -> Option<Duration> {
+        clock(ClockId::ProcessCPUTime)
+    }
+
+    /// Reads `cutime` and `cstime` from `/proc/self/stat`, the same values `getrusage` reports.
+    pub(super) fn children() -> Option<Duration> {
+        let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+        // The command name, in parentheses, may contain spaces. The fields after it start
+        // with the third field of the file: `cutime` and `cstime` are the 16th and 17th.
+        let mut fields = stat
+            .get(stat.rfind(')')? + 1..)?
+            .split_whitespace()
+            .skip(13);
+        let cutime: u64 = fields.next()?.parse().ok()?;
+        let cstime: u64 = fields.next()?.parse().ok()?;
+
+        let per_second = rustix::param::clock_ticks_per_second();
+        let ticks = cutime + cstime;
+        Some(
+            Duration::from_secs(ticks / per_second)
+                + Duration::from_nanos((ticks % per_second) * 1_000_000_000 / per_second),
+        )
+    }
+}
+
+#[cfg(all(windows, not(feature = "forbid-unsafe")))]
+mod sys {
+    use std::ffi::c_void;
+    use std::mem::{self, MaybeUninit};
+    use std::ptr;
+    use std::time::Duration;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct FileTime {
+        low: u32,
+        high: u32,
+    }
+
+    #[repr(C)]
+    struct JobAccounting {
+        total_user_time: i64,
+        total_kernel_time: i64,
+        this_period_total_user_time: i64,
+        this_period_total_kernel_time: i64,
+        total_page_fault_count: u32,
+        total_processes: u32,
+        active_processes: u32,
+        total_terminated_processes: u32,
+    }
+
+    /// `JobObjectBasicAccountingInformation`
+    const JOB_ACCOUNTING: i32 = 1;
+
+    type Times = unsafe extern "system" fn(
+        *mut c_void,
+        *mut FileTime,
+        *mut FileTime,
+        *mut FileTime,
+        *mut FileTime,
+    ) -> i32;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetCurrentProcess() -> *mut c_void;
+        fn GetCurrentThread() -> *mut c_void;
+        fn GetProcessTimes(
+            process: *mut c_void,
+            creation: *mut FileTime,
+            exit: *mut FileTime,
+            kernel: *mut FileTime,
+            user: *mut FileTime,
+        ) -> i32;
+        fn GetThreadTimes(
+            thread: *mut c_void,
+            creation: *mut FileTime,
+            exit: *mut FileTime,
+            kernel: *mut FileTime,
+            user: *mut FileTime,
+        ) -> i32;
+        fn QueryInformationJobObject(
+            job: *mut c_void,
+            class: i32,
+            info: *mut c_void,
+            length: u32,
+            return_length: *mut u32,
+        ) -> i32;
+    }
+
+    /// Converts a count of 100 ns units to a duration.
+    fn units(units: u64) -> Duration {
+        Duration::from_nanos(units.saturating_mul(100))
+    }
+
+    fn times(get: Times, handle: *mut c_void) -> Option<u64> {
+        let zero = FileTime { low: 0, high: 0 };
+        let (mut creation, mut exit, mut kernel, mut user) = (zero, zero, zero, zero);
+        if unsafe { get(handle, &mut creation, &mut exit, &mut kernel, &mut user) } == 0 {
+            return None;
+        }
+        let count = |t: FileTime| u64::from(t.high) << 32 | u64::from(t.low);
+        Some(count(kernel) + count(user))
+    }
+
+    pub(super) fn thread() -> Option<Duration> {
+        times(GetThreadTimes, unsafe { GetCurrentThread() }).map(units)
+    }
+
+    pub(super) fn process() -> Option<Duration> {
+        times(GetProcessTimes, unsafe { GetCurrentProcess() }).map(units)
+    }
+
+    pub(super) fn children() -> Option<Duration> {
+        let mut info = MaybeUninit::<JobAccounting>::uninit();
+        // A null handle refers to the job of the current process.
+        let queried = unsafe {
+            QueryInformationJobObject(
+                ptr::null_mut(),
+                JOB_ACCOUNTING,
+                info.as_mut_ptr().cast(),
+                mem::size_of::<JobAccounting>() as u32,
+                ptr::null_mut(),
+            )
+        };
+        if queried == 0 {
+            return None;
+        }
+        let info = unsafe { info.assume_init() };
+
+        let job = (info.total_user_time + info.total_kernel_time).max(0) as u64;
+        let own = times(GetProcessTimes, unsafe { GetCurrentProcess() })?;
+        Some(units(job.saturating_sub(own)))
+    }
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    all(
+        any(target_os = "macos", target_os = "ios", windows),
+        not(feature = "forbid-unsafe")
+    )
+)))]
+mod sys {
+    use std::time::Duration;
+
+    pub(super) fn thread() -> Option<Duration> {
+        None
+    }
+
+    pub(super) fn process() -> Option<Duration> {
+        None
+    }
+
+    pub(super) fn children() -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn counts_children() {
+        let before = cpu_time_children().unwrap();
+        let status = std::process::Command::new("sh")
+            .args(["-c", "i=0; while [ $i -lt 20000 ]; do i=$((i+1)); done"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert!(cpu_time_children().unwrap() > before);
+    }
+
+    #[test]
+    fn thread_within_process() {
+        let v: u64 = (0..1_000_000u64).map(|i| i ^ (i >> 3)).sum();
+        assert!(v > 0);
+        if let (Some(thread), Some(process)) = (cpu_time_thread(), cpu_time_process()) {
+            assert!(thread <= process);
+        }
+    }
+}
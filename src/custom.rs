@@ -0,0 +1,101 @@
+//! Timestamp implementation without the standard library, with the `std` feature disabled.
+//!
+//! There's no operating system to ask for the time. The application registers a function
+//! reading its clock with [`set_clock_source`], e.g. a hardware timer or an RTOS tick counter.
+
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::Readings;
+
+/// The registered source, a `fn() -> u64` stored as a pointer, null until registered.
+static SOURCE: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Registers the function that reads the clock, in nanoseconds.
+///
+/// It should keep counting while the system sleeps, and it must never go backwards.
+/// Until a source is registered, the clock reads zero.
+///
+/// Without the standard library this is what [`now`](crate::now), [`Instant`](crate::Instant)
+/// and [`Timestamp`](crate::Timestamp) read.
+///
+/// ```
+/// fn ticks() -> u64 {
+///     42
+/// }
+///
+/// zeitstempel::set_clock_source(ticks);
+/// assert_eq!(42, zeitstempel::now());
+/// ```
+pub fn set_clock_source(source: fn() -> u64) {
+    SOURCE.store(source as *mut (), Ordering::Release);
+}
+
+/// Reads the clock through the source, or zero if none is registered.
+fn read() -> Option<u64> {
+    let source = SOURCE.load(Ordering::Acquire);
+    if source.is_null() {
+        return None;
+    }
+
+    let source = unsafe { core::mem::transmute::<*mut (), fn() -> u64>(source) };
+    Some(source())
+}
+
+/// The clock read by the registered source.
+pub fn now_including_suspend() -> u64 {
+    read().unwrap_or(0)
+}
+
+/// Like [`now_including_suspend`], but reports when no source is registered yet.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    read().ok_or(Error::Unavailable)
+}
+
+/// There's a single source, so this is the same clock as [`now_including_suspend`].
+pub fn now_excluding_suspend() -> u64 {
+    now_including_suspend()
+}
+
+/// There's a single clock, read once for both.
+pub fn now_both() -> Readings {
+    Readings::same(now_including_suspend())
+}
+
+/// The source returns nanoseconds already.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: now_including_suspend(),
+        unit: TickUnit::Nanoseconds,
+    }
+}
+
+/// [`raw_now_including_suspend`] counts nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// What the source reads is up to the application, it should include suspend time.
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        name: "clock source",
+        resolution: None,
+        includes_suspend: true,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reads_registered_source() {
+        assert_eq!(Err(Error::Unavailable), try_now_including_suspend());
+
+        set_clock_source(|| 1_000);
+        assert_eq!(Ok(1_000), try_now_including_suspend());
+        assert_eq!(1_000, crate::Instant::now().as_timestamp());
+    }
+}
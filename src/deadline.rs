@@ -0,0 +1,96 @@
+use core::convert::TryInto;
+use core::time::Duration;
+
+use crate::instant::{ClockKind, IncludingSuspend, Instant};
+
+/// A point in time by which something has to happen, e.g. a request timeout or token expiry.
+///
+/// Like [`Instant`] it says which clock it's measured on. On the default suspend-including
+/// clock, time the system spends in sleep or hibernation counts towards the deadline.
+/// With the `serde` feature it serializes like the [`Instant`] it expires at,
+/// so it survives restarts of the process, but not reboots.
+///
+/// ```
+/// use std::time::Duration;
+/// use zeitstempel::Deadline;
+///
+/// let token_expiry = Deadline::after(Duration::from_secs(3600));
+/// assert!(!token_expiry.is_expired());
+/// assert!(token_expiry.remaining().unwrap() <= Duration::from_secs(3600));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline<C: ClockKind = IncludingSuspend> {
+    pub(crate) at: Instant<C>,
+}
+
+impl Deadline<IncludingSuspend> {
+    /// Returns a deadline `timeout` from now on the suspend-including clock.
+    pub fn after(timeout: Duration) -> Deadline {
+        Deadline::new(Instant::now(), timeout)
+    }
+}
+
+impl<C: ClockKind> Deadline<C> {
+    /// Returns a deadline `timeout` after `start`.
+    ///
+    /// Saturates at the latest representable instant.
+    pub fn new(start: Instant<C>, timeout: Duration) -> Deadline<C> {
+        let timeout = timeout.as_nanos().try_into().unwrap_or(u64::MAX);
+        Deadline::at(Instant::from_nanos(
+            start.as_timestamp().saturating_add(timeout),
+        ))
+    }
+
+    /// Returns a deadline expiring at `instant`.
+    pub fn at(instant: Instant<C>) -> Deadline<C> {
+        Deadline { at: instant }
+    }
+
+    /// Returns the instant the deadline expires at.
+    pub fn instant(&self) -> Instant<C> {
+        self.at
+    }
+
+    /// Returns whether the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_none()
+    }
+
+    /// Returns the time left until the deadline, or `None` if it has passed.
+    pub fn remaining(&self) -> Option<Duration> {
+        let now = Instant::<C>::read().as_timestamp();
+        match self.at.as_timestamp().checked_sub(now) {
+            Some(nanos) if nanos > 0 => Some(Duration::from_nanos(nanos)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+    use crate::ExcludingSuspend;
+
+    #[test]
+    fn expires() {
+        let start = Instant::now_excluding_suspend();
+        let expired: Deadline<ExcludingSuspend> = Deadline::new(start, Duration::from_nanos(0));
+        assert!(expired.is_expired());
+        assert_eq!(None, expired.remaining());
+
+        let pending = Deadline::new(start, Duration::from_secs(60));
+        assert!(!pending.is_expired());
+        assert!(pending.remaining().unwrap() > Duration::from_secs(59));
+        assert_eq!(
+            Duration::from_secs(60),
+            pending.instant().duration_since(start)
+        );
+    }
+
+    #[test]
+    fn saturates() {
+        let never = Deadline::after(Duration::MAX);
+        assert_eq!(u64::MAX, never.instant().as_timestamp());
+        assert!(!never.is_expired());
+    }
+}
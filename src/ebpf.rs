@@ -0,0 +1,65 @@
+//! Correlating timestamps with eBPF traces, on Linux and Android only.
+//!
+//! Kernel tracing tools timestamp events with BPF helpers reading kernel clocks.
+//! `bpf_ktime_get_boot_ns` reads `CLOCK_BOOTTIME`, the very clock behind [`now`](crate::now):
+//! both have the same starting point and rate, so their values can be merged directly.
+//!
+//! `bpf_ktime_get_ns` reads `CLOCK_MONOTONIC`, which stops during suspend.
+//! Tools like `perf` may also use `CLOCK_MONOTONIC_RAW`, which additionally isn't adjusted by
+//! time synchronization. Their values are translated using the current offset between the
+//! clocks, which is exact only for events since the last resume.
+
+use crate::linux;
+
+/// Converts a `bpf_ktime_get_boot_ns` value to a timestamp comparable with [`now`](crate::now).
+///
+/// This is the identity: both use `CLOCK_BOOTTIME`.
+pub fn from_ktime_get_boot_ns(ns: u64) -> u64 {
+    ns
+}
+
+/// Converts a timestamp from [`now`](crate::now) to a `bpf_ktime_get_boot_ns` value.
+///
+/// This is the identity: both use `CLOCK_BOOTTIME`.
+pub fn to_ktime_get_boot_ns(ts: u64) -> u64 {
+    ts
+}
+
+/// Converts a `bpf_ktime_get_ns` (`CLOCK_MONOTONIC`) value to a timestamp comparable with
+/// [`now`](crate::now).
+pub fn from_ktime_get_ns(ns: u64) -> u64 {
+    ns.saturating_add(linux::offset_to(libc::CLOCK_MONOTONIC))
+}
+
+/// Converts a `CLOCK_MONOTONIC_RAW` value to a timestamp comparable with [`now`](crate::now).
+///
+/// The raw clock runs at a slightly different rate, so this gets less precise the further the
+/// value lies in the past.
+pub fn from_monotonic_raw_ns(ns: u64) -> u64 {
+    ns.saturating_add(linux::offset_to(libc::CLOCK_MONOTONIC_RAW))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_clock_as_ktime_get_boot_ns() {
+        // `bpf_ktime_get_boot_ns` reads `CLOCK_BOOTTIME`, same as user space's `clock_gettime`.
+        let before = linux::clock_gettime(libc::CLOCK_BOOTTIME);
+        let now = crate::now();
+        let after = linux::clock_gettime(libc::CLOCK_BOOTTIME);
+
+        assert!(before <= from_ktime_get_boot_ns(now) && now <= after);
+        assert_eq!(now, to_ktime_get_boot_ns(from_ktime_get_boot_ns(now)));
+    }
+
+    #[test]
+    fn translates_monotonic() {
+        let monotonic = linux::clock_gettime(libc::CLOCK_MONOTONIC);
+        let now = crate::now();
+        let translated = from_ktime_get_ns(monotonic);
+
+        assert!(translated.max(now) - translated.min(now) < 1_000_000);
+    }
+}
@@ -0,0 +1,23 @@
+use core::fmt;
+
+/// Why [`try_now`](crate::try_now) couldn't read the clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The clock doesn't exist on this system, e.g. in an SGX enclave without a clock hook.
+    Unavailable,
+    /// The operating system failed to read the clock, with this error code.
+    Os(i32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Unavailable => write!(f, "the clock is not available on this system"),
+            Error::Os(code) => write!(f, "reading the clock failed with OS error {}", code),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -0,0 +1,72 @@
+//! Timestamp implementation for ESP-IDF, e.g. on the ESP32.
+//!
+//! `esp_timer_get_time` counts microseconds since boot.
+//! ESP-IDF adjusts it by the time spent in light sleep, so it includes suspend time.
+//! Waking up from deep sleep restarts the chip, and with it the clock.
+
+use std::convert::TryInto;
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::Readings;
+
+/// One tick of `esp_timer_get_time` is a microsecond.
+const MICROSECONDS: TickUnit = TickUnit::Timebase {
+    numer: 1000,
+    denom: 1,
+};
+
+extern "C" {
+    /// Gets the time in microseconds since boot.
+    ///
+    /// See [`esp_timer_get_time`].
+    ///
+    /// [`esp_timer_get_time`]: https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/esp_timer.html
+    fn esp_timer_get_time() -> i64;
+}
+
+/// The time from `esp_timer_get_time`, in nanoseconds.
+/// This includes the time spent in light sleep.
+pub fn now_including_suspend() -> u64 {
+    raw_now_including_suspend().to_nanos()
+}
+
+/// `esp_timer_get_time` can't fail.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    Ok(now_including_suspend())
+}
+
+/// There's no clock telling light sleep apart,
+/// so this is the same clock as [`now_including_suspend`].
+pub fn now_excluding_suspend() -> u64 {
+    now_including_suspend()
+}
+
+/// There's a single clock, read once for both.
+pub fn now_both() -> Readings {
+    Readings::same(now_including_suspend())
+}
+
+/// The time from `esp_timer_get_time` in its native unit of microseconds.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: unsafe { esp_timer_get_time() }.try_into().unwrap_or(0),
+        unit: MICROSECONDS,
+    }
+}
+
+/// `esp_timer_get_time` counts microseconds.
+pub fn tick_unit() -> TickUnit {
+    MICROSECONDS
+}
+
+/// `esp_timer_get_time`, which counts microseconds.
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        name: "esp_timer_get_time",
+        resolution: Some(Duration::from_micros(1)),
+        includes_suspend: true,
+    }
+}
@@ -3,6 +3,11 @@ use std::time::Instant;
 
 use once_cell::sync::Lazy;
 
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::Readings;
+
 static INIT_TIME: Lazy<Instant> = Lazy::new(Instant::now);
 
 pub fn now_including_suspend() -> u64 {
@@ -29,3 +34,42 @@ pub fn now_including_suspend() -> u64 {
         .and_then(|diff| diff.as_nanos().try_into().ok())
         .unwrap_or(0)
 }
+
+/// `std::time::Instant` can't fail.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    Ok(now_including_suspend())
+}
+
+/// The fallback has no way to tell suspend time apart,
+/// so this is the same clock as [`now_including_suspend`].
+pub fn now_excluding_suspend() -> u64 {
+    now_including_suspend()
+}
+
+/// There's a single clock, read once for both.
+pub fn now_both() -> Readings {
+    Readings::same(now_including_suspend())
+}
+
+/// The fallback counts nanoseconds already.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: now_including_suspend(),
+        unit: TickUnit::Nanoseconds,
+    }
+}
+
+/// [`raw_now_including_suspend`] counts nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// `std::time::Instant` includes suspend time on Windows, where it's `QueryPerformanceCounter`.
+/// Elsewhere it's not guaranteed.
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        name: "std::time::Instant",
+        resolution: None,
+        includes_suspend: cfg!(windows),
+    }
+}
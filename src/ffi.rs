@@ -0,0 +1,151 @@
+//! Functions for use from C and other languages, with the `ffi` feature.
+//!
+//! Embedders loading the library at runtime should check [`zeitstempel_abi_version`]
+//! against the [`ZEITSTEMPEL_ABI_VERSION`] they were built with before calling anything else.
+//!
+//! The declarations are in `include/zeitstempel.h`, generated by [cbindgen] with
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --output include/zeitstempel.h src/ffi.rs
+//! ```
+//!
+//! CI runs the same command and fails if the result differs from the checked-in header.
+//!
+//! Build a library to link against with
+//!
+//! ```text
+//! cargo rustc --release --lib --features ffi --crate-type staticlib
+//! ```
+//!
+//! or `--crate-type cdylib` for a shared library.
+//!
+//! [cbindgen]: https://github.com/mozilla/cbindgen
+
+use std::ffi::CString;
+use std::os::raw::c_char;
+
+use once_cell::sync::Lazy;
+
+/// The version of the C ABI.
+///
+/// It's increased whenever an exported function is removed or changes its signature or
+/// meaning. Adding functions keeps the version, so newer libraries work with older callers.
+pub const ZEITSTEMPEL_ABI_VERSION: u32 = 1;
+
+/// Returns the [`ZEITSTEMPEL_ABI_VERSION`] the library was built with.
+///
+/// This function is part of every version of the ABI.
+#[no_mangle]
+pub extern "C" fn zeitstempel_abi_version() -> u32 {
+    ZEITSTEMPEL_ABI_VERSION
+}
+
+/// Returns a timestamp corresponding to "now", in nanoseconds, like [`now`](crate::now).
+///
+/// It includes the time the system spent in suspend where the platform supports it.
+#[no_mangle]
+pub extern "C" fn zeitstempel_now() -> u64 {
+    crate::now()
+}
+
+/// Returns a timestamp corresponding to "now", in nanoseconds, without the time the system spent
+/// in suspend, like [`now_excluding_suspend`](crate::now_excluding_suspend).
+#[no_mangle]
+pub extern "C" fn zeitstempel_now_excluding_suspend() -> u64 {
+    crate::now_excluding_suspend()
+}
+
+/// Returns [`zeitstempel_now`] as a signed integer, like [`now_i64`](crate::now_i64).
+///
+/// Timestamps that don't fit saturate to `INT64_MAX`.
+#[no_mangle]
+pub extern "C" fn zeitstempel_now_i64() -> i64 {
+    crate::now_i64()
+}
+
+/// Returns [`zeitstempel_now_excluding_suspend`] as a signed integer.
+///
+/// Timestamps that don't fit saturate to `INT64_MAX`.
+#[no_mangle]
+pub extern "C" fn zeitstempel_now_excluding_suspend_i64() -> i64 {
+    crate::saturating_i64(crate::now_excluding_suspend())
+}
+
+/// Describes the clock behind [`zeitstempel_now`], see [`ClockInfo`](crate::ClockInfo).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZeitstempelClockInfo {
+    /// The name of the operating system's clock or function, NUL-terminated.
+    /// It's valid until the process exits.
+    pub name: *const c_char,
+    /// The resolution the clock reports for itself, in nanoseconds, or 0 if it doesn't.
+    pub resolution_ns: u64,
+    /// Whether time the system spends in sleep or hibernation is counted on this platform.
+    pub includes_suspend: bool,
+}
+
+/// The clock's name, kept for the pointer handed out.
+static CLOCK_NAME: Lazy<CString> =
+    Lazy::new(|| CString::new(crate::clock_info().name).unwrap_or_default());
+
+/// Describes the clock behind [`zeitstempel_now`], like [`clock_info`](crate::clock_info).
+#[no_mangle]
+pub extern "C" fn zeitstempel_clock_info() -> ZeitstempelClockInfo {
+    let info = crate::clock_info();
+    ZeitstempelClockInfo {
+        name: CLOCK_NAME.as_ptr(),
+        resolution_ns: info.resolution.map_or(0, |resolution| {
+            resolution.as_nanos().min(u64::MAX.into()) as u64
+        }),
+        includes_suspend: info.includes_suspend,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn abi_version() {
+        assert_eq!(ZEITSTEMPEL_ABI_VERSION, zeitstempel_abi_version());
+    }
+
+    #[test]
+    fn signed() {
+        extern "C" {
+            fn zeitstempel_now_i64() -> i64;
+            fn zeitstempel_now_excluding_suspend_i64() -> i64;
+        }
+
+        let before = crate::now() as i64;
+        let now = unsafe { zeitstempel_now_i64() };
+        assert!(before <= now && now <= crate::now() as i64);
+
+        let before = crate::now_excluding_suspend() as i64;
+        let now = unsafe { zeitstempel_now_excluding_suspend_i64() };
+        assert!(before <= now && now <= crate::now_excluding_suspend() as i64);
+    }
+
+    #[test]
+    fn clock_info() {
+        let info = zeitstempel_clock_info();
+        let name = unsafe { std::ffi::CStr::from_ptr(info.name) };
+        assert_eq!(crate::clock_info().name, name.to_str().unwrap());
+        assert_eq!(crate::clock_info().includes_suspend, info.includes_suspend);
+    }
+
+    #[test]
+    fn header_declares_functions() {
+        let header = include_str!("../include/zeitstempel.h");
+        for declaration in [
+            "uint32_t zeitstempel_abi_version(void);",
+            "uint64_t zeitstempel_now(void);",
+            "uint64_t zeitstempel_now_excluding_suspend(void);",
+            "int64_t zeitstempel_now_i64(void);",
+            "int64_t zeitstempel_now_excluding_suspend_i64(void);",
+            "ZeitstempelClockInfo zeitstempel_clock_info(void);",
+        ] {
+            assert!(header.contains(declaration), "{} is missing", declaration);
+        }
+    }
+}
@@ -0,0 +1,367 @@
+//! Formatting timestamps and durations without allocating.
+//!
+//! Everything here writes into a [`core::fmt::Write`] or a caller-provided byte buffer,
+//! so it's usable from panic handlers, signal handlers and `no_std` logging backends
+//! where `format!` is unavailable.
+//!
+//! ```
+//! use zeitstempel::fmt::format_timestamp;
+//!
+//! let mut buf = [0u8; 32];
+//! assert_eq!(Some("12.000000345"), format_timestamp(12_000_000_345, &mut buf));
+//! ```
+//!
+//! For debug logs and command lines, [`Timestamp`] displays as nanoseconds, e.g.
+//! `123456789ns`, [`SinceBoot`] as the time since the clock started, e.g.
+//! `2h13m7.5s since boot`, and [`Hms`] displays a duration the same way.
+//! All of them parse their output back with [`FromStr`]:
+//!
+//! ```
+//! use std::time::Duration;
+//! use zeitstempel::fmt::{Hms, SinceBoot};
+//! use zeitstempel::Timestamp;
+//!
+//! let ts = Timestamp::from(7_987_500_000_000);
+//! assert_eq!("7987500000000ns", ts.to_string());
+//! assert_eq!("2h13m7.5s since boot", SinceBoot(ts).to_string());
+//! assert_eq!(Ok(ts), "2h13m7.5s since boot".parse());
+//!
+//! let duration: Hms = "1m30s".parse().unwrap();
+//! assert_eq!(Duration::from_secs(90), duration.0);
+//! ```
+
+use core::convert::TryInto;
+use core::fmt::{self, Write};
+use core::str::{self, FromStr};
+use core::time::Duration;
+
+use crate::Timestamp;
+
+const NS_PER_S: u128 = 1_000_000_000;
+
+/// Writes a timestamp as seconds with nanosecond precision, e.g. `12.000000345`.
+pub fn write_timestamp<W: Write>(writer: &mut W, ts: u64) -> fmt::Result {
+    write!(writer, "{}.{:09}", ts / 1_000_000_000, ts % 1_000_000_000)
+}
+
+/// Writes a duration in the largest fitting unit of `s`, `ms`, `µs` and `ns`,
+/// with three decimals, e.g. `1.500s`, `12.345ms` or `900ns`.
+pub fn write_duration<W: Write>(writer: &mut W, duration: Duration) -> fmt::Result {
+    let nanos = duration.as_nanos();
+    let (unit, scale) = match nanos {
+        0..=999 => return write!(writer, "{}ns", nanos),
+        1_000..=999_999 => ("µs", 1_000),
+        1_000_000..=999_999_999 => ("ms", 1_000_000),
+        _ => ("s", 1_000_000_000),
+    };
+    let thousandths = scale / 1_000;
+    write!(
+        writer,
+        "{}.{:03}{}",
+        nanos / scale,
+        nanos % scale / thousandths,
+        unit
+    )
+}
+
+/// A [`Write`] into a byte buffer, failing once the buffer is full.
+#[derive(Debug)]
+pub struct Buffer<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Buffer<'a> {
+    /// Starts writing at the beginning of `buf`.
+    pub fn new(buf: &'a mut [u8]) -> Buffer<'a> {
+        Buffer { buf, len: 0 }
+    }
+
+    /// Returns what was written so far.
+    pub fn as_str(&self) -> &str {
+        // Only whole `str`s are ever copied in.
+        str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+
+    /// Returns what was written, borrowing from the buffer.
+    pub fn into_str(self) -> &'a str {
+        str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+}
+
+impl Write for Buffer<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len.checked_add(s.len()).ok_or(fmt::Error)?;
+        let target = self.buf.get_mut(self.len..end).ok_or(fmt::Error)?;
+        target.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Formats a timestamp like [`write_timestamp`] into `buf`.
+///
+/// Returns `None` if `buf` is too small. 31 bytes always suffice.
+pub fn format_timestamp(ts: u64, buf: &mut [u8]) -> Option<&str> {
+    let mut buffer = Buffer::new(buf);
+    write_timestamp(&mut buffer, ts).ok()?;
+    Some(buffer.into_str())
+}
+
+/// Formats a duration like [`write_duration`] into `buf`.
+///
+/// Returns `None` if `buf` is too small. 32 bytes always suffice.
+pub fn format_duration(duration: Duration, buf: &mut [u8]) -> Option<&str> {
+    let mut buffer = Buffer::new(buf);
+    write_duration(&mut buffer, duration).ok()?;
+    Some(buffer.into_str())
+}
+
+/// A string isn't a timestamp or duration in the format of this module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError;
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid timestamp or duration")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// A duration displayed in hours, minutes and seconds, e.g. `2h13m7.5s`.
+///
+/// Units that are zero are left out, a zero duration is `0s`.
+/// Parsing also accepts `ms`, `us`, `µs` and `ns`, in any combination, e.g. `1s500ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hms(pub Duration);
+
+impl fmt::Display for Hms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.0.as_secs();
+        let (hours, minutes, seconds) = (secs / 3600, secs % 3600 / 60, secs % 60);
+        let nanos = self.0.subsec_nanos();
+
+        if hours > 0 {
+            write!(f, "{}h", hours)?;
+        }
+        if minutes > 0 {
+            write!(f, "{}m", minutes)?;
+        }
+        if seconds == 0 && nanos == 0 && secs > 0 {
+            return Ok(());
+        }
+        write!(f, "{}", seconds)?;
+        if nanos > 0 {
+            let mut buf = [0u8; 9];
+            let mut fraction = Buffer::new(&mut buf);
+            write!(fraction, "{:09}", nanos)?;
+            write!(f, ".{}", fraction.as_str().trim_end_matches('0'))?;
+        }
+        write!(f, "s")
+    }
+}
+
+impl FromStr for Hms {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Hms, ParseError> {
+        parse_hms(s).map(Hms)
+    }
+}
+
+impl From<Hms> for Duration {
+    fn from(duration: Hms) -> Duration {
+        duration.0
+    }
+}
+
+impl From<Duration> for Hms {
+    fn from(duration: Duration) -> Hms {
+        Hms(duration)
+    }
+}
+
+/// Parses a sequence of numbers with units, e.g. `2h13m7.5s`.
+///
+/// Fractions are accepted as long as they're whole nanoseconds.
+fn parse_hms(s: &str) -> Result<Duration, ParseError> {
+    if s.is_empty() {
+        return Err(ParseError);
+    }
+
+    let mut nanos: u128 = 0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let (whole, after) = split_digits(rest);
+        let (fraction, after) = match after.strip_prefix('.') {
+            Some(after) => split_digits(after),
+            None => ("", after),
+        };
+        let unit_len = after
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(after.len());
+        let (unit, after) = after.split_at(unit_len);
+        rest = after;
+
+        let scale: u128 = match unit {
+            "h" => 3600 * NS_PER_S,
+            "m" => 60 * NS_PER_S,
+            "s" => NS_PER_S,
+            "ms" => 1_000_000,
+            "us" | "µs" => 1_000,
+            "ns" => 1,
+            _ => return Err(ParseError),
+        };
+        if whole.is_empty() || fraction.len() > 9 {
+            return Err(ParseError);
+        }
+
+        let whole: u128 = whole.parse().map_err(|_| ParseError)?;
+        let mut value = whole.checked_mul(scale).ok_or(ParseError)?;
+        if !fraction.is_empty() {
+            let denom = 10u128.pow(fraction.len() as u32);
+            #[allow(clippy::manual_is_multiple_of)] // `is_multiple_of` needs Rust 1.87.
+            if scale % denom != 0 {
+                return Err(ParseError);
+            }
+            let fraction: u128 = fraction.parse().map_err(|_| ParseError)?;
+            value += fraction * (scale / denom);
+        }
+        nanos = nanos.checked_add(value).ok_or(ParseError)?;
+    }
+
+    let secs = (nanos / NS_PER_S).try_into().map_err(|_| ParseError)?;
+    Ok(Duration::new(secs, (nanos % NS_PER_S) as u32))
+}
+
+/// Splits `s` after its leading ASCII digits.
+fn split_digits(s: &str) -> (&str, &str) {
+    let len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s.split_at(len)
+}
+
+/// Displays the timestamp in nanoseconds, e.g. `123456789ns`.
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}ns", self.0)
+    }
+}
+
+/// Parses the formats of [`Timestamp`] and [`SinceBoot`]: `123456789ns` or
+/// `2h13m7.5s since boot`.
+impl FromStr for Timestamp {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Timestamp, ParseError> {
+        if let Some(since_boot) = s.strip_suffix(" since boot") {
+            let nanos = parse_hms(since_boot)?.as_nanos();
+            return nanos.try_into().map(Timestamp).map_err(|_| ParseError);
+        }
+
+        let nanos = s.strip_suffix("ns").ok_or(ParseError)?;
+        if nanos.is_empty() || !nanos.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseError);
+        }
+        nanos.parse().map(Timestamp).map_err(|_| ParseError)
+    }
+}
+
+/// A timestamp displayed as the time since the clock started, e.g. `2h13m7.5s since boot`.
+///
+/// On most systems the clock starts at boot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SinceBoot(pub Timestamp);
+
+impl fmt::Display for SinceBoot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} since boot", Hms(Duration::from_nanos(self.0 .0)))
+    }
+}
+
+impl FromStr for SinceBoot {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<SinceBoot, ParseError> {
+        if !s.ends_with(" since boot") {
+            return Err(ParseError);
+        }
+        s.parse().map(SinceBoot)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn formats_into_buffers() {
+        let mut buf = [0u8; 32];
+        assert_eq!(Some("0.000000000"), format_timestamp(0, &mut buf));
+        assert_eq!(
+            Some("18446744073.709551615"),
+            format_timestamp(u64::MAX, &mut buf)
+        );
+        assert_eq!(None, format_timestamp(u64::MAX, &mut buf[..10]));
+
+        let mut buf = [0u8; 32];
+        let mut format = |d| format_duration(d, &mut buf).map(|s| s.to_string());
+        assert_eq!(Some("900ns".into()), format(Duration::from_nanos(900)));
+        assert_eq!(Some("1.500µs".into()), format(Duration::from_nanos(1_500)));
+        assert_eq!(
+            Some("12.345ms".into()),
+            format(Duration::from_micros(12_345))
+        );
+        assert_eq!(Some("1.500s".into()), format(Duration::from_millis(1_500)));
+
+        let mut buf = [0u8; 32];
+        assert!(format_duration(Duration::MAX, &mut buf).is_some());
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut nanos = 0u64;
+        for step in [1, 999, 1_000_000, 59_999_999_999, 3_600_000_000_000, 7_919] {
+            for _ in 0..50 {
+                nanos = nanos.wrapping_mul(31).wrapping_add(step);
+                let ts = Timestamp(nanos);
+                assert_eq!(Ok(ts), ts.to_string().parse());
+                assert_eq!(Ok(SinceBoot(ts)), SinceBoot(ts).to_string().parse());
+            }
+        }
+
+        let max = Timestamp::MAX;
+        assert_eq!(Ok(max), max.to_string().parse());
+        assert_eq!(Ok(SinceBoot(max)), SinceBoot(max).to_string().parse());
+        assert_eq!(
+            Ok(Hms(Duration::MAX)),
+            Hms(Duration::MAX).to_string().parse()
+        );
+    }
+
+    #[test]
+    fn human_readable() {
+        let hms = |nanos| Hms(Duration::from_nanos(nanos)).to_string();
+        assert_eq!("0s", hms(0));
+        assert_eq!("0.000000001s", hms(1));
+        assert_eq!("2h", hms(7_200_000_000_000));
+        assert_eq!("1h5s", hms(3_605_000_000_000));
+        assert_eq!("2h13m7.5s", hms(7_987_500_000_000));
+
+        let parse = |s: &str| s.parse::<Hms>().map(Duration::from);
+        assert_eq!(Ok(Duration::from_millis(1_500)), parse("1s500ms"));
+        assert_eq!(Ok(Duration::from_nanos(1_500)), parse("1.5µs"));
+        assert_eq!(Ok(Duration::from_micros(2)), parse("2us"));
+        assert_eq!(Err(ParseError), parse(""));
+        assert_eq!(Err(ParseError), parse("5"));
+        assert_eq!(Err(ParseError), parse("1.5ns"));
+        assert_eq!(Err(ParseError), parse("1d"));
+        assert_eq!(Err(ParseError), parse(".5s"));
+
+        assert_eq!(Err(ParseError), "12".parse::<Timestamp>());
+        assert_eq!(Err(ParseError), "-1ns".parse::<Timestamp>());
+        assert_eq!(Err(ParseError), "1ns".parse::<SinceBoot>());
+        assert_eq!(Err(ParseError), "6000000h since boot".parse::<Timestamp>());
+    }
+}
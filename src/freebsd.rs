@@ -0,0 +1,60 @@
+//! Timestamp implementation for FreeBSD and DragonFly BSD.
+//!
+//! `CLOCK_MONOTONIC` keeps counting while the system is suspended on most configurations,
+//! `CLOCK_UPTIME` counts the time the system has been running.
+
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::posix;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::{bracketed, Readings};
+
+/// The time from `CLOCK_MONOTONIC`, or zero if it can't be read.
+///
+/// See [`clock_gettime`].
+///
+/// [`clock_gettime`]: https://man.freebsd.org/cgi/man.cgi?query=clock_gettime
+pub fn now_including_suspend() -> u64 {
+    try_now_including_suspend().unwrap_or(0)
+}
+
+/// Reports when the kernel fails to read `CLOCK_MONOTONIC`.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    posix::clock_gettime(libc::CLOCK_MONOTONIC)
+}
+
+/// The time from `CLOCK_UPTIME`, or zero if it can't be read.
+///
+/// See [`clock_gettime`].
+///
+/// [`clock_gettime`]: https://man.freebsd.org/cgi/man.cgi?query=clock_gettime
+pub fn now_excluding_suspend() -> u64 {
+    posix::clock_gettime(libc::CLOCK_UPTIME).unwrap_or(0)
+}
+
+/// Reads both clocks back-to-back.
+pub fn now_both() -> Readings {
+    bracketed(now_including_suspend, now_excluding_suspend)
+}
+
+/// The same clock as [`now_including_suspend`], which counts nanoseconds already.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: now_including_suspend(),
+        unit: TickUnit::Nanoseconds,
+    }
+}
+
+/// [`raw_now_including_suspend`] counts nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// `CLOCK_MONOTONIC`, with the resolution reported by `clock_getres`.
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        name: "CLOCK_MONOTONIC",
+        resolution: posix::clock_getres(libc::CLOCK_MONOTONIC),
+        includes_suspend: true,
+    }
+}
@@ -0,0 +1,71 @@
+//! Timestamp implementation for Fuchsia.
+//!
+//! Fuchsia has two timelines: the boot timeline keeps counting while the system is suspended,
+//! the monotonic one doesn't. Both are read from the vDSO without a system call.
+//! `zx_clock_get_boot` needs a Fuchsia release that provides the boot timeline.
+
+use std::convert::TryInto;
+
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::{bracketed, Readings};
+
+#[link(name = "zircon")]
+extern "C" {
+    /// Gets the time on the boot timeline, in nanoseconds.
+    ///
+    /// See [`zx_clock_get_boot`].
+    ///
+    /// [`zx_clock_get_boot`]: https://fuchsia.dev/reference/syscalls/clock_get_boot
+    fn zx_clock_get_boot() -> i64;
+
+    /// Gets the time on the monotonic timeline, in nanoseconds.
+    ///
+    /// See [`zx_clock_get_monotonic`].
+    ///
+    /// [`zx_clock_get_monotonic`]: https://fuchsia.dev/reference/syscalls/clock_get_monotonic
+    fn zx_clock_get_monotonic() -> i64;
+}
+
+/// The time on the boot timeline, which includes suspend time.
+pub fn now_including_suspend() -> u64 {
+    unsafe { zx_clock_get_boot() }.try_into().unwrap_or(0)
+}
+
+/// Reading the boot timeline can't fail.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    Ok(now_including_suspend())
+}
+
+/// The time on the monotonic timeline, which doesn't include suspend time.
+pub fn now_excluding_suspend() -> u64 {
+    unsafe { zx_clock_get_monotonic() }.try_into().unwrap_or(0)
+}
+
+/// Reads both clocks back-to-back.
+pub fn now_both() -> Readings {
+    bracketed(now_including_suspend, now_excluding_suspend)
+}
+
+/// The boot timeline counts nanoseconds already.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: now_including_suspend(),
+        unit: TickUnit::Nanoseconds,
+    }
+}
+
+/// [`raw_now_including_suspend`] counts nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// The boot timeline, which doesn't report its resolution.
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        name: "zx_clock_get_boot",
+        resolution: None,
+        includes_suspend: true,
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::Mutex;
+
+/// Suspends shorter than this aren't counted.
+/// Reading the two clocks one after the other leaves a gap of a few microseconds,
+/// which must not look like a suspend.
+pub(crate) const MIN_SUSPEND: u64 = 10_000_000;
+
+/// A process-wide count of the suspends detected so far, see [`generation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Generation(u64);
+
+impl Generation {
+    /// Returns whether no suspend was detected since this generation was taken.
+    ///
+    /// ```
+    /// let generation = zeitstempel::generation();
+    /// // ... build a cache, open connections ...
+    /// if !generation.is_current() {
+    ///     // The system slept in between: refresh everything.
+    /// }
+    /// ```
+    pub fn is_current(self) -> bool {
+        generation() == self
+    }
+}
+
+struct State {
+    /// The suspend time observed last, in nanoseconds.
+    suspended: Option<u64>,
+    generation: u64,
+}
+
+impl State {
+    /// Records the total suspend time since boot, returning the generation.
+    fn observe(&mut self, suspended: u64) -> u64 {
+        match self.suspended {
+            Some(last) if suspended < last.saturating_add(MIN_SUSPEND) => {}
+            Some(_) => {
+                self.suspended = Some(suspended);
+                self.generation += 1;
+            }
+            None => self.suspended = Some(suspended),
+        }
+        self.generation
+    }
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    suspended: None,
+    generation: 0,
+});
+
+/// Returns the current suspend generation.
+///
+/// It increases whenever the system was suspended since the previous call,
+/// so caches and connection pools can cheaply tell whether to revalidate their state after
+/// sleep, without subscribing to operating system events.
+///
+/// Suspends are detected from the gap between the [`now`](crate::now) and
+/// [`now_excluding_suspend`](crate::now_excluding_suspend) clocks, read together with
+/// [`now_both`](crate::now_both) on every call.
+/// Several suspends between two calls increase the generation only once.
+/// Where the platform can't tell suspend time apart, the generation never changes.
+pub fn generation() -> Generation {
+    let readings = crate::now_both();
+    let suspended = readings
+        .including_suspend
+        .saturating_sub(readings.excluding_suspend);
+
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    Generation(state.observe(suspended))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counts_suspends() {
+        let mut state = State {
+            suspended: None,
+            generation: 0,
+        };
+        assert_eq!(0, state.observe(5_000_000_000));
+        assert_eq!(0, state.observe(5_000_000_001));
+        assert_eq!(1, state.observe(8_000_000_000));
+        assert_eq!(1, state.observe(8_000_000_000));
+        assert_eq!(2, state.observe(9_000_000_000));
+    }
+
+    #[test]
+    fn current() {
+        let generation = generation();
+        assert!(generation.is_current());
+    }
+}
@@ -0,0 +1,70 @@
+//! A clock for the [governor] rate limiter, available with the `governor` feature.
+//!
+//! Rate limiters built with [`GovernorClock`] replenish their quota while the system is
+//! suspended, too.
+//!
+//! ```
+//! use std::num::NonZeroU32;
+//! use governor::{Quota, RateLimiter};
+//! use zeitstempel::governor_clock::GovernorClock;
+//!
+//! let quota = Quota::per_second(NonZeroU32::new(10).unwrap());
+//! let limiter = RateLimiter::direct_with_clock(quota, GovernorClock);
+//! assert!(limiter.check().is_ok());
+//! ```
+//!
+//! [governor]: https://docs.rs/governor
+
+use std::ops::Add;
+
+use governor::clock::{Clock, ReasonablyRealtime, Reference};
+use governor::nanos::Nanos;
+
+use crate::Timestamp;
+
+/// A [`governor`] clock reading [`now`](crate::now).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GovernorClock;
+
+impl Clock for GovernorClock {
+    type Instant = Timestamp;
+
+    fn now(&self) -> Timestamp {
+        Timestamp(crate::now())
+    }
+}
+
+impl ReasonablyRealtime for GovernorClock {}
+
+impl Reference for Timestamp {
+    fn duration_since(&self, earlier: Self) -> Nanos {
+        Nanos::from(self.0.saturating_sub(earlier.0))
+    }
+
+    fn saturating_sub(&self, duration: Nanos) -> Self {
+        Timestamp(self.0.saturating_sub(duration.as_u64()))
+    }
+}
+
+impl Add<Nanos> for Timestamp {
+    type Output = Timestamp;
+
+    fn add(self, duration: Nanos) -> Timestamp {
+        Timestamp(self.0.saturating_add(duration.as_u64()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reference() {
+        let earlier = Timestamp(1_000);
+        let later = earlier + Nanos::from(500);
+
+        assert_eq!(Nanos::from(500), later.duration_since(earlier));
+        assert_eq!(Nanos::from(0), earlier.duration_since(later));
+        assert_eq!(earlier, Reference::saturating_sub(&later, Nanos::from(500)));
+    }
+}
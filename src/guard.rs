@@ -0,0 +1,157 @@
+//! The guard keeping clocks from going backwards.
+//!
+//! Backends that can't trust their clock to be monotonic, and caches of recent readings,
+//! remember the latest value handed out and never return anything earlier.
+//!
+//! Its concurrency is checked with [loom]:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --release --lib guard
+//! ```
+//!
+//! [loom]: https://docs.rs/loom
+
+// Only some backends and features use the guard.
+#![allow(dead_code)]
+
+#[cfg(loom)]
+use loom::sync::atomic::{AtomicU64, Ordering};
+#[cfg(not(loom))]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The latest reading of a clock, only ever moving forward.
+pub(crate) struct Latest(AtomicU64);
+
+impl Latest {
+    /// Starts at zero, before any reading. Usable in statics.
+    #[cfg(not(loom))]
+    pub(crate) const fn new() -> Latest {
+        Latest(AtomicU64::new(0))
+    }
+
+    /// Starts at zero, before any reading.
+    #[cfg(loom)]
+    pub(crate) fn new() -> Latest {
+        Latest(AtomicU64::new(0))
+    }
+
+    /// Records `reading` and returns it, or the latest value if `reading` is earlier.
+    pub(crate) fn advance(&self, reading: u64) -> u64 {
+        self.0.fetch_max(reading, Ordering::Relaxed).max(reading)
+    }
+
+    /// Returns the latest value, `0` before the first reading.
+    pub(crate) fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// The guard of `now` lives in statics, which loom's atomics can't be used in.
+#[cfg(all(feature = "monotonic", loom))]
+compile_error!("the `monotonic` feature can't be checked with loom");
+
+/// The latest value [`now`](crate::now) returned, with the `monotonic` feature.
+#[cfg(feature = "monotonic")]
+static NOW: Latest = Latest::new();
+
+/// How often [`NOW`] held back a reading.
+#[cfg(feature = "monotonic")]
+static CORRECTIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Returns `reading`, or the latest value [`now`](crate::now) returned if it's earlier.
+#[cfg(feature = "monotonic")]
+pub(crate) fn monotonic(reading: u64) -> u64 {
+    let latest = NOW.advance(reading);
+    if latest > reading {
+        CORRECTIONS.fetch_add(1, Ordering::Relaxed);
+    }
+    latest
+}
+
+/// Returns how often the clock went backwards and [`now`](crate::now) returned the latest
+/// timestamp instead, with the `monotonic` feature.
+///
+/// A correct monotonic clock never does. Some virtualized environments have buggy clocks
+/// that occasionally step back; a growing count reported by telemetry points to them.
+#[cfg(feature = "monotonic")]
+pub fn monotonic_corrections() -> u64 {
+    CORRECTIONS.load(Ordering::Relaxed)
+}
+
+#[cfg(all(test, feature = "monotonic", not(loom)))]
+mod monotonic_test {
+    use super::{monotonic, monotonic_corrections};
+
+    #[test]
+    fn holds_back_earlier_readings() {
+        let latest = monotonic(crate::sys::now_including_suspend());
+        let corrections = monotonic_corrections();
+
+        assert_eq!(latest, monotonic(latest / 2));
+        assert!(monotonic_corrections() > corrections);
+        assert!(crate::now() >= latest);
+    }
+
+    #[test]
+    fn leaves_raw_readings_alone() {
+        let corrections = monotonic_corrections();
+        let raw = crate::raw_now();
+
+        assert_eq!(crate::sys::raw_now_including_suspend().unit, raw.unit);
+        assert_eq!(corrections, monotonic_corrections());
+    }
+}
+
+#[cfg(all(test, loom))]
+mod test {
+    use super::*;
+    use loom::sync::Arc;
+    use loom::thread;
+
+    #[test]
+    fn never_goes_backwards() {
+        loom::model(|| {
+            let latest = Arc::new(Latest::new());
+
+            let writers: Vec<_> = [(5, 10), (8, 3)]
+                .iter()
+                .map(|&(first, second)| {
+                    let latest = latest.clone();
+                    thread::spawn(move || {
+                        let a = latest.advance(first);
+                        let b = latest.advance(second);
+                        assert!(a >= first);
+                        assert!(b >= a);
+                        b
+                    })
+                })
+                .collect();
+
+            let first = latest.get();
+            let second = latest.get();
+            assert!(second >= first);
+
+            let results: Vec<u64> = writers.into_iter().map(|w| w.join().unwrap()).collect();
+            assert_eq!(10, latest.get());
+            assert!(results.iter().all(|&r| r <= 10));
+        });
+    }
+
+    #[test]
+    fn get_sees_own_advance() {
+        loom::model(|| {
+            let latest = Arc::new(Latest::new());
+
+            let other = {
+                let latest = latest.clone();
+                thread::spawn(move || latest.advance(7))
+            };
+
+            let seen = latest.advance(4);
+            assert!(latest.get() >= seen);
+
+            assert!(other.join().unwrap() >= 7);
+            assert_eq!(7, latest.get());
+        });
+    }
+}
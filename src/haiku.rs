@@ -0,0 +1,58 @@
+//! Timestamp implementation for Haiku.
+//!
+//! `CLOCK_MONOTONIC` is the time since boot, like `system_time`.
+//! Haiku doesn't properly suspend to RAM yet, and it's not documented whether the clock
+//! would count it, so [`clock_info`] doesn't claim so. There's no clock telling suspend time apart.
+
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::posix;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::Readings;
+
+/// The time from `CLOCK_MONOTONIC`, or zero if it can't be read.
+///
+/// See [`clock_gettime`].
+///
+/// [`clock_gettime`]: https://pubs.opengroup.org/onlinepubs/9699919799/functions/clock_gettime.html
+pub fn now_including_suspend() -> u64 {
+    try_now_including_suspend().unwrap_or(0)
+}
+
+/// Reports when the kernel fails to read `CLOCK_MONOTONIC`.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    posix::clock_gettime(libc::CLOCK_MONOTONIC)
+}
+
+/// There's no clock telling suspend time apart,
+/// so this is the same clock as [`now_including_suspend`].
+pub fn now_excluding_suspend() -> u64 {
+    now_including_suspend()
+}
+
+/// There's a single clock, read once for both.
+pub fn now_both() -> Readings {
+    Readings::same(now_including_suspend())
+}
+
+/// The same clock as [`now_including_suspend`], which counts nanoseconds already.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: now_including_suspend(),
+        unit: TickUnit::Nanoseconds,
+    }
+}
+
+/// [`raw_now_including_suspend`] counts nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// `CLOCK_MONOTONIC`, with the resolution reported by `clock_getres`.
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        name: "CLOCK_MONOTONIC",
+        resolution: posix::clock_getres(libc::CLOCK_MONOTONIC),
+        includes_suspend: false,
+    }
+}
@@ -0,0 +1,64 @@
+//! Timestamp implementation for illumos and Solaris.
+//!
+//! `gethrtime` is the high-resolution monotonic clock, read without a system call.
+//! Suspend to RAM is rare on these systems, and it's not documented whether the clock counts
+//! it, so [`clock_info`] doesn't claim so. There's no clock telling suspend time apart.
+
+use std::convert::TryInto;
+
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::Readings;
+
+extern "C" {
+    /// Gets the high-resolution time in nanoseconds since an arbitrary point in the past.
+    ///
+    /// See [`gethrtime`].
+    ///
+    /// [`gethrtime`]: https://illumos.org/man/3C/gethrtime
+    fn gethrtime() -> i64;
+}
+
+/// The time from `gethrtime`.
+pub fn now_including_suspend() -> u64 {
+    unsafe { gethrtime() }.try_into().unwrap_or(0)
+}
+
+/// `gethrtime` can't fail.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    Ok(now_including_suspend())
+}
+
+/// There's no clock telling suspend time apart,
+/// so this is the same clock as [`now_including_suspend`].
+pub fn now_excluding_suspend() -> u64 {
+    now_including_suspend()
+}
+
+/// There's a single clock, read once for both.
+pub fn now_both() -> Readings {
+    Readings::same(now_including_suspend())
+}
+
+/// `gethrtime` counts nanoseconds already.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: now_including_suspend(),
+        unit: TickUnit::Nanoseconds,
+    }
+}
+
+/// [`raw_now_including_suspend`] counts nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// `gethrtime`, which doesn't report its resolution.
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        name: "gethrtime",
+        resolution: None,
+        includes_suspend: false,
+    }
+}
@@ -0,0 +1,21 @@
+use core::time::Duration;
+
+/// Describes the clock behind [`now`](crate::now) on this system, e.g. for telemetry.
+///
+/// ```
+/// let info = zeitstempel::clock_info();
+/// println!(
+///     "{} with a resolution of {:?}, includes suspend: {}",
+///     info.name, info.resolution, info.includes_suspend
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ClockInfo {
+    /// The name of the operating system's clock or function, e.g. `CLOCK_BOOTTIME`.
+    pub name: &'static str,
+    /// The resolution the clock reports for itself, if it does.
+    pub resolution: Option<Duration>,
+    /// Whether time the system spends in sleep or hibernation is counted on this platform.
+    pub includes_suspend: bool,
+}
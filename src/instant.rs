@@ -0,0 +1,288 @@
+use core::convert::TryInto;
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::marker::PhantomData;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::time::Duration;
+
+/// The clock an [`Instant`] measures, [`IncludingSuspend`] or [`ExcludingSuspend`].
+///
+/// This trait is sealed, it can't be implemented outside of this crate.
+pub trait ClockKind: Debug + Copy + Ord + Hash + private::Sealed {}
+
+pub(crate) mod private {
+    pub trait Sealed {
+        /// How the clock is named in serialized instants.
+        #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+        const NAME: &'static str;
+
+        fn now() -> u64;
+    }
+}
+
+/// Marks instants of the suspend-including clock, like [`now`](crate::now).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct IncludingSuspend;
+
+/// Marks instants of the suspend-excluding clock, like
+/// [`now_excluding_suspend`](crate::now_excluding_suspend).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ExcludingSuspend;
+
+impl ClockKind for IncludingSuspend {}
+impl ClockKind for ExcludingSuspend {}
+
+impl private::Sealed for IncludingSuspend {
+    const NAME: &'static str = "including-suspend";
+
+    fn now() -> u64 {
+        crate::now()
+    }
+}
+
+impl private::Sealed for ExcludingSuspend {
+    const NAME: &'static str = "excluding-suspend";
+
+    fn now() -> u64 {
+        crate::now_excluding_suspend()
+    }
+}
+
+/// A measurement of a monotonic clock, similar to [`std::time::Instant`].
+///
+/// The type parameter says which clock was measured.
+/// `Instant<IncludingSuspend>`, the default, comes from [`Instant::now`] or
+/// [`Instant::now_including_suspend`]: the difference between two of them includes time the
+/// system was in sleep or hibernation.
+/// `Instant<ExcludingSuspend>` comes from [`Instant::now_excluding_suspend`] and doesn't count it.
+///
+/// Instants of different clocks can't be compared or subtracted:
+///
+/// ```compile_fail
+/// use zeitstempel::Instant;
+///
+/// let including = Instant::now_including_suspend();
+/// let excluding = Instant::now_excluding_suspend();
+/// including.duration_since(excluding);
+/// ```
+///
+/// Like [`std::time::Instant`], durations can be added and subtracted and instants subtracted
+/// from each other, but the results saturate instead of panicking.
+///
+/// Unlike [`std::time::Instant`], they can be turned into a plain timestamp with
+/// [`as_timestamp`](Instant::as_timestamp), e.g. to serialize them.
+/// With the `serde` feature they implement `Serialize` and `Deserialize`,
+/// as a versioned record of the timestamp and the clock it came from:
+/// `{"version":1,"clock":"including-suspend","nanos":123456789}`.
+/// Deserializing an instant of the other clock fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant<C: ClockKind = IncludingSuspend> {
+    nanos: u64,
+    clock: PhantomData<C>,
+}
+
+impl Instant<IncludingSuspend> {
+    /// Returns an instant corresponding to "now" on the suspend-including clock.
+    pub fn now() -> Instant {
+        Instant::now_including_suspend()
+    }
+
+    /// Returns an instant corresponding to "now" on the suspend-including clock,
+    /// like [`now`](crate::now).
+    pub fn now_including_suspend() -> Instant {
+        Instant::read()
+    }
+}
+
+impl Instant<ExcludingSuspend> {
+    /// Returns an instant corresponding to "now" on the suspend-excluding clock,
+    /// like [`now_excluding_suspend`](crate::now_excluding_suspend).
+    pub fn now_excluding_suspend() -> Instant<ExcludingSuspend> {
+        Instant::read()
+    }
+}
+
+impl<C: ClockKind> Instant<C> {
+    pub(crate) fn read() -> Instant<C> {
+        Instant::from_nanos(C::now())
+    }
+
+    pub(crate) fn from_nanos(nanos: u64) -> Instant<C> {
+        Instant {
+            nanos,
+            clock: PhantomData,
+        }
+    }
+
+    /// Returns the time elapsed since this instant.
+    ///
+    /// Returns a zero duration if this instant is later than "now".
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(C::now().saturating_sub(self.nanos))
+    }
+
+    /// Returns the time elapsed from `earlier` to this instant.
+    ///
+    /// Returns a zero duration if `earlier` is later than this instant.
+    pub fn duration_since(&self, earlier: Instant<C>) -> Duration {
+        self.saturating_duration_since(earlier)
+    }
+
+    /// Returns the time elapsed from `earlier` to this instant,
+    /// or `None` if `earlier` is later than this instant.
+    pub fn checked_duration_since(&self, earlier: Instant<C>) -> Option<Duration> {
+        self.nanos
+            .checked_sub(earlier.nanos)
+            .map(Duration::from_nanos)
+    }
+
+    /// Returns the time elapsed from `earlier` to this instant,
+    /// or a zero duration if `earlier` is later than this instant.
+    pub fn saturating_duration_since(&self, earlier: Instant<C>) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or_default()
+    }
+
+    /// Returns the timestamp of this instant, in nanoseconds.
+    ///
+    /// For instants on the suspend-including clock that's the value [`now`](crate::now)
+    /// returned at the time.
+    pub fn as_timestamp(&self) -> u64 {
+        self.nanos
+    }
+
+    /// Returns the timestamp of this instant, in whole microseconds.
+    pub fn as_timestamp_us(&self) -> u64 {
+        self.nanos / 1_000
+    }
+
+    /// Returns the timestamp of this instant, in whole milliseconds.
+    pub fn as_timestamp_ms(&self) -> u64 {
+        self.nanos / 1_000_000
+    }
+
+    /// Returns the time elapsed since this instant and resets it to "now".
+    ///
+    /// Both happen based on a single reading of the clock,
+    /// so no time is lost between consecutive calls, e.g. when timing loop iterations:
+    ///
+    /// ```
+    /// let mut instant = zeitstempel::Instant::now();
+    /// for _ in 0..3 {
+    ///     // ...
+    ///     println!("iteration took {:?}", instant.elapsed_reset());
+    /// }
+    /// ```
+    pub fn elapsed_reset(&mut self) -> Duration {
+        let now = Instant::read();
+        let elapsed = now.duration_since(*self);
+        *self = now;
+        elapsed
+    }
+}
+
+/// Saturates at the latest representable instant.
+impl<C: ClockKind> Add<Duration> for Instant<C> {
+    type Output = Instant<C>;
+
+    fn add(self, duration: Duration) -> Instant<C> {
+        Instant::from_nanos(self.nanos.saturating_add(nanos(duration)))
+    }
+}
+
+impl<C: ClockKind> AddAssign<Duration> for Instant<C> {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+/// Saturates at zero, the start of the clock.
+impl<C: ClockKind> Sub<Duration> for Instant<C> {
+    type Output = Instant<C>;
+
+    fn sub(self, duration: Duration) -> Instant<C> {
+        Instant::from_nanos(self.nanos.saturating_sub(nanos(duration)))
+    }
+}
+
+impl<C: ClockKind> SubAssign<Duration> for Instant<C> {
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = *self - duration;
+    }
+}
+
+/// Returns the time elapsed from `earlier` to `self`, or zero if `earlier` is later,
+/// see [`Instant::duration_since`].
+impl<C: ClockKind> Sub for Instant<C> {
+    type Output = Duration;
+
+    fn sub(self, earlier: Instant<C>) -> Duration {
+        self.saturating_duration_since(earlier)
+    }
+}
+
+/// A duration in nanoseconds, saturating at `u64::MAX`.
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos().try_into().unwrap_or(u64::MAX)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn elapsed_reset() {
+        let mut instant = Instant::now();
+        let start = instant;
+        thread::sleep(Duration::from_millis(2));
+
+        let first = instant.elapsed_reset();
+        assert!(first >= Duration::from_millis(2));
+        assert!(instant > start);
+        assert!(instant.elapsed() < start.elapsed());
+    }
+
+    #[test]
+    fn excluding_suspend() {
+        let start = Instant::now_excluding_suspend();
+        thread::sleep(Duration::from_millis(2));
+        let end = Instant::now_excluding_suspend();
+
+        assert!(end.duration_since(start) >= Duration::from_millis(2));
+        assert_eq!(Duration::from_secs(0), start.duration_since(end));
+        assert!(start.as_timestamp() <= crate::now_excluding_suspend());
+    }
+
+    #[test]
+    fn checked_duration_since() {
+        let earlier = Instant::<IncludingSuspend>::from_nanos(1_000);
+        let later = Instant::from_nanos(1_500);
+
+        assert_eq!(
+            Some(Duration::from_nanos(500)),
+            later.checked_duration_since(earlier)
+        );
+        assert_eq!(Some(Duration::ZERO), later.checked_duration_since(later));
+        assert_eq!(None, earlier.checked_duration_since(later));
+        assert_eq!(Duration::ZERO, earlier.saturating_duration_since(later));
+    }
+
+    #[test]
+    fn operators() {
+        let mut instant = Instant::<IncludingSuspend>::from_nanos(1_000);
+        instant += Duration::from_nanos(500);
+        assert_eq!(1_500, instant.as_timestamp());
+        assert_eq!(
+            Duration::from_nanos(500),
+            instant - Instant::from_nanos(1_000)
+        );
+        assert_eq!(Duration::ZERO, Instant::from_nanos(1_000) - instant);
+
+        instant -= Duration::from_secs(1);
+        assert_eq!(0, instant.as_timestamp());
+        assert_eq!(
+            u64::MAX,
+            (Instant::<IncludingSuspend>::from_nanos(1) + Duration::MAX).as_timestamp()
+        );
+    }
+}
@@ -0,0 +1,56 @@
+//! Correlating timestamps with systemd journal entries, on Linux only.
+//!
+//! The journal records `__MONOTONIC_TIMESTAMP` in microseconds of `CLOCK_MONOTONIC`,
+//! which stops while the system is suspended, together with the boot's `_BOOT_ID`.
+//! Timestamps from [`now`](crate::now) use `CLOCK_BOOTTIME`, which keeps counting.
+//! The two differ by the total time the system spent in suspend since boot.
+//!
+//! The conversions use the current difference. They are exact for timestamps taken since the
+//! last resume; for earlier timestamps they are off by the time suspended since then.
+
+use crate::boot;
+use crate::linux;
+
+/// The time spent in suspend since boot, in nanoseconds.
+fn suspended() -> u64 {
+    linux::offset_to(libc::CLOCK_MONOTONIC)
+}
+
+/// Converts a timestamp from [`now`](crate::now) to a journal `__MONOTONIC_TIMESTAMP`.
+pub fn to_journal_monotonic(ts: u64) -> u64 {
+    ts.saturating_sub(suspended()) / 1_000
+}
+
+/// Converts a journal `__MONOTONIC_TIMESTAMP` to a timestamp comparable with [`now`](crate::now).
+pub fn from_journal_monotonic(usec: u64) -> u64 {
+    usec.saturating_mul(1_000).saturating_add(suspended())
+}
+
+/// Returns the current boot's ID in the format of the journal's `_BOOT_ID` field.
+///
+/// Only journal entries with this boot ID can be correlated with timestamps.
+pub fn boot_id() -> Option<String> {
+    boot::boot_id().map(|id| format!("{:032x}", id))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let ts = crate::now();
+        let usec = to_journal_monotonic(ts);
+
+        assert!(usec <= linux::now_excluding_suspend() / 1_000);
+        let back = from_journal_monotonic(usec);
+        assert!(ts.max(back) - ts.min(back) < 1_000_000);
+    }
+
+    #[test]
+    fn boot_id_format() {
+        let id = boot_id().unwrap();
+        assert_eq!(32, id.len());
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
@@ -0,0 +1,48 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+thread_local! {
+    static LAST_LAP: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Returns the time since the previous call to `lap` on the current thread.
+///
+/// The first call on a thread returns `None` and starts measuring.
+/// The duration includes time the system spent in suspend.
+///
+/// Meant for quick performance debugging:
+///
+/// ```
+/// zeitstempel::lap();
+/// let v: Vec<u64> = (0..1000).collect();
+/// println!("collecting took {:?}", zeitstempel::lap().unwrap());
+/// # drop(v);
+/// ```
+pub fn lap() -> Option<Duration> {
+    let now = crate::now();
+    LAST_LAP
+        .with(|last| last.replace(Some(now)))
+        .map(|last| Duration::from_nanos(now.saturating_sub(last)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn first_lap_per_thread() {
+        thread::spawn(|| {
+            assert_eq!(None, lap());
+            thread::sleep(Duration::from_millis(2));
+            let before = crate::now();
+            assert!(lap().unwrap() >= Duration::from_millis(2));
+
+            // Only the time since the previous lap counts.
+            let lap = lap().unwrap();
+            assert!(lap <= Duration::from_nanos(crate::now() - before));
+        })
+        .join()
+        .unwrap();
+    }
+}
@@ -32,8 +32,7 @@
 //! let start = zeitstempel::now();
 //! thread::sleep(Duration::from_millis(2));
 //!
-//! let diff = Duration::from_nanos(zeitstempel::now() - start);
-//! assert!(diff >= Duration::from_millis(2));
+//! zeitstempel::assert_elapsed!(start, >= Duration::from_millis(2));
 //! ```
 //!
 //! # Supported operating systems
@@ -42,35 +41,343 @@
 //!
 //! * Windows\*
 //! * macOS
-//! * Linux
+//! * Linux. On IBM Z, the `s390x-tod` feature reads the TOD clock without a system call.
+//!   On x86-64, the `tsc` feature reads the invariant TSC without a system call,
+//!   on AArch64 the `aarch64-counter` feature the generic timer's virtual counter.
 //! * Android
-//! * iOS
+//! * FreeBSD and DragonFly BSD
+//! * OpenBSD and NetBSD
+//! * illumos and Solaris. It's not documented whether their clock includes suspend time.
+//! * Fuchsia, on releases with the boot timeline
+//! * QNX Neutrino. It's not documented whether its clock includes suspend time.
+//! * Redox, which doesn't suspend
+//! * Haiku. It's not documented whether its clock includes suspend time.
+//! * ESP-IDF, including time spent in light sleep
+//! * iOS, including Mac Catalyst
+//! * Browsers, with the `wasm-js` feature. Timestamps are comparable across Web Workers.
+//! * WASI runtimes, reading the host's monotonic clock
+//! * SGX enclaves such as `x86_64-fortanix-unknown-sgx`, with a clock provided by the host
+//!   through a usercall (see `sgx::set_clock_hook`)
 //!
 //! For other operating systems there's a fallback to `std::time::Instant`,
 //! compared against a process-global fixed reference point.
 //! We don't guarantee that measured time includes time the system spends in sleep or hibernation.
 //!
-//! \* To use native Windows 10 functionality enable the `win10plus` feature. Otherwise it will use the fallback.
+//! \* Windows 7 and 8 lack `QueryInterruptTime`, detected at runtime. There the millisecond-resolution
+//! `GetTickCount64` is used instead. The `win10plus` feature has no effect anymore.
+//!
+//! The `kuser-shared-data` feature additionally reads the interrupt time from the memory Windows shares
+//! with every process, without a function call.
+//! The `precise-interrupt-time` feature uses `QueryInterruptTimePrecise` instead, which is precise to
+//! 100 nanoseconds rather than to the timer interrupt interval of usually 15.6 milliseconds.
+//!
+//! Enable the `strict` feature, or its older name `deny-fallback`, to turn the use of the fallback
+//! into a compile error. The fallback's timestamps don't include suspend time and restart with
+//! every process, so this makes sure such a change of meaning is noticed at build time.
+//!
+//! With the `wallclock-fallback` feature, other operating systems use the wall clock instead.
+//! It includes suspend time and survives restarts of the process, but it can jump when the
+//! system time is changed. Backward jumps are clamped, so timestamps never decrease.
+//!
+//! The `forbid-unsafe` feature compiles the crate with `#![forbid(unsafe_code)]`,
+//! for builds that must not depend on unsafe code.
+//! Linux and Android read their clocks through [rustix] then, all other operating systems use the
+//! fallback. The `ffi`, `python` and `uniffi` features need unsafe code to export their functions
+//! and lift the guarantee.
+//!
+//! The `ffi` feature exports `zeitstempel_now`, `zeitstempel_now_excluding_suspend`, their signed
+//! variants `zeitstempel_now_i64` and `zeitstempel_now_excluding_suspend_i64`,
+//! `zeitstempel_clock_info` and `zeitstempel_abi_version` for C and C++, declared in
+//! `include/zeitstempel.h`.
+//!
+//! The `uniffi` feature exports `now`, `now_excluding_suspend` and boot timestamps for Kotlin and
+//! Swift bindings generated by UniFFI.
+//!
+//! The `python` feature builds a Python extension module with `now`, `clock_info` and helpers for
+//! timestamp arithmetic, for tools that replay timestamps or create comparable ones.
+//!
+//! The `std` feature is enabled by default. Without it the crate is `no_std`: there's no
+//! operating system to read the clock from, so the application registers a function reading
+//! its clock with `set_clock_source`. `Timestamp`, `Instant`, their arithmetic and, with the
+//! `serde` feature, their serialization are available then. Everything else needs the standard
+//! library. Registering the clock source needs unsafe code, so `forbid-unsafe` has no effect.
+//!
+//! The `testing` feature replaces the clocks with a virtual clock that tests can set, advance and
+//! freeze through `zeitstempel::test`, so code reading `now()` is tested without sleeping.
+//!
+//! Some virtualized environments have clocks that occasionally step backwards. With the `monotonic`
+//! feature `now()` never returns a timestamp earlier than a previous one within the process,
+//! and `monotonic_corrections()` counts how often it had to hold the clock back.
+//! `raw_now()`, `now_ms()` and `now_us()` aren't held back, so raw ticks keep their native unit.
+//!
+//! The `suspend-events` feature adds a `SuspendMonitor`, calling back after the system resumed from
+//! suspend with how long it was suspended.
+//!
+//! The `async-tokio` feature adds `sleep`, `timeout` and `interval` futures for tokio in
+//! `zeitstempel::timer`, whose deadlines include time spent in suspend, unlike tokio's own timers.
+//!
+//! The `tracing` feature adds a log timestamp for `tracing_subscriber::fmt` and a layer reporting
+//! span durations in `zeitstempel::trace`, both including time spent in suspend.
+//!
+//! [rustix]: https://docs.rs/rustix
 
 #![deny(missing_docs)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(
+    all(
+        feature = "forbid-unsafe",
+        feature = "std",
+        not(any(feature = "ffi", feature = "python", feature = "uniffi"))
+    ),
+    forbid(unsafe_code)
+)]
 #![deny(broken_intra_doc_links)]
 
+use core::convert::TryInto;
+use core::time::Duration;
+
+#[macro_use]
+mod macros;
+
 cfg_if::cfg_if! {
-    if #[cfg(any(target_os = "macos", target_os = "ios"))] {
+    if #[cfg(not(feature = "std"))] {
+        mod custom;
+        use custom as sys;
+    } else if #[cfg(all(any(target_os = "macos", target_os = "ios"), not(feature = "forbid-unsafe")))] {
         mod mac;
         use mac as sys;
+    } else if #[cfg(all(
+        target_os = "linux",
+        target_arch = "s390x",
+        feature = "s390x-tod",
+        not(feature = "forbid-unsafe")
+    ))] {
+        // Provides the excluding clock and the Linux-specific helpers.
+        #[allow(dead_code)]
+        mod linux;
+        mod s390x;
+        use s390x as sys;
+    } else if #[cfg(all(
+        any(target_os = "linux", target_os = "android"),
+        target_arch = "x86_64",
+        feature = "tsc",
+        not(feature = "forbid-unsafe")
+    ))] {
+        // The TSC is paired with `CLOCK_BOOTTIME`, which is the fallback as well.
+        mod calibration;
+        mod linux;
+        mod tsc;
+        use tsc as sys;
+    } else if #[cfg(all(
+        any(target_os = "linux", target_os = "android"),
+        target_arch = "aarch64",
+        feature = "aarch64-counter",
+        not(feature = "forbid-unsafe")
+    ))] {
+        // The counter is paired with `CLOCK_BOOTTIME`, which is the fallback as well.
+        mod aarch64;
+        mod calibration;
+        mod linux;
+        use aarch64 as sys;
     } else if #[cfg(any(target_os = "linux", target_os = "android"))] {
         mod linux;
         use linux as sys;
-    } else if #[cfg(all(windows, feature = "win10plus"))] {
+    } else if #[cfg(all(windows, not(feature = "forbid-unsafe")))] {
         mod win;
         use win as sys;
+    } else if #[cfg(all(
+        target_arch = "wasm32",
+        target_os = "unknown",
+        feature = "wasm-js",
+        not(feature = "forbid-unsafe")
+    ))] {
+        mod wasm;
+        use wasm as sys;
+    } else if #[cfg(all(
+        any(target_os = "freebsd", target_os = "dragonfly"),
+        not(feature = "forbid-unsafe")
+    ))] {
+        mod freebsd;
+        mod posix;
+        use freebsd as sys;
+    } else if #[cfg(all(target_os = "openbsd", not(feature = "forbid-unsafe")))] {
+        mod openbsd;
+        mod posix;
+        use openbsd as sys;
+    } else if #[cfg(all(target_os = "netbsd", not(feature = "forbid-unsafe")))] {
+        mod netbsd;
+        mod posix;
+        use netbsd as sys;
+    } else if #[cfg(all(
+        any(target_os = "illumos", target_os = "solaris"),
+        not(feature = "forbid-unsafe")
+    ))] {
+        mod illumos;
+        use illumos as sys;
+    } else if #[cfg(all(target_os = "fuchsia", not(feature = "forbid-unsafe")))] {
+        mod fuchsia;
+        use fuchsia as sys;
+    } else if #[cfg(all(target_os = "nto", not(feature = "forbid-unsafe")))] {
+        mod nto;
+        mod posix;
+        use nto as sys;
+    } else if #[cfg(all(target_os = "redox", not(feature = "forbid-unsafe")))] {
+        mod posix;
+        mod redox;
+        use redox as sys;
+    } else if #[cfg(all(target_os = "haiku", not(feature = "forbid-unsafe")))] {
+        mod haiku;
+        mod posix;
+        use haiku as sys;
+    } else if #[cfg(all(target_os = "espidf", not(feature = "forbid-unsafe")))] {
+        mod espidf;
+        use espidf as sys;
+    } else if #[cfg(all(target_os = "wasi", not(feature = "forbid-unsafe")))] {
+        mod posix;
+        mod wasi;
+        use wasi as sys;
+    } else if #[cfg(all(target_env = "sgx", not(feature = "forbid-unsafe")))] {
+        pub mod sgx;
+        use sgx as sys;
+    } else if #[cfg(all(not(windows), feature = "wallclock-fallback"))] {
+        mod wallclock;
+        use wallclock as sys;
+    } else if #[cfg(feature = "deny-fallback")] {
+        compile_error!(
+            "zeitstempel has no suspend-aware clock for this target and the `strict` or \
+             `deny-fallback` feature is enabled."
+        );
     } else {
         mod fallback;
         use fallback as sys;
     }
 }
 
+mod deadline;
+mod error;
+pub mod fmt;
+mod info;
+mod instant;
+pub mod raw;
+mod readings;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod timestamp;
+
+#[cfg(not(feature = "std"))]
+pub use custom::set_clock_source;
+pub use deadline::Deadline;
+pub use error::Error;
+pub use info::ClockInfo;
+pub use instant::{ClockKind, ExcludingSuspend, IncludingSuspend, Instant};
+pub use raw::{RawTimestamp, TickUnit};
+pub use readings::Readings;
+pub use timestamp::Timestamp;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::macros::{elapsed_failed, Elapsed, Start};
+}
+
+// The functions exported for the bindings live in `uniffi_bindings`.
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
+
+// Everything else needs threads, allocations or the operating system.
+cfg_if::cfg_if! {
+    if #[cfg(feature = "std")] {
+        mod anchor;
+        mod boot;
+        mod boot_timestamp;
+        pub mod bucket;
+        mod budget;
+        pub mod channel;
+        mod clock;
+        #[cfg(feature = "coarse")]
+        mod coarse;
+        #[cfg(feature = "compat")]
+        pub mod compat;
+        #[cfg(feature = "humantime")]
+        pub mod config;
+        mod cpu;
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        pub mod ebpf;
+        #[cfg(feature = "ffi")]
+        pub mod ffi;
+        mod generation;
+        #[cfg(feature = "governor")]
+        pub mod governor_clock;
+        mod guard;
+        #[cfg(target_os = "linux")]
+        pub mod journal;
+        mod lap;
+        #[cfg(feature = "metrics")]
+        pub mod metrics_timer;
+        pub mod offset;
+        #[cfg(feature = "opentelemetry")]
+        pub mod otel;
+        mod pause;
+        pub mod persist;
+        pub mod profile;
+        #[cfg(feature = "python")]
+        mod python;
+        #[cfg(feature = "quanta")]
+        pub mod quanta_clock;
+        pub mod retry;
+        mod scheduler;
+        mod session;
+        #[cfg(all(
+            feature = "apple-signpost",
+            any(target_os = "macos", target_os = "ios"),
+            not(feature = "forbid-unsafe")
+        ))]
+        mod signpost;
+        #[cfg(target_os = "linux")]
+        pub mod steal;
+        mod stopwatch;
+        #[cfg(feature = "suspend-events")]
+        mod suspend_monitor;
+        pub mod sync;
+        #[cfg(feature = "testing")]
+        pub mod test;
+        mod ticker;
+        pub mod timed;
+        #[cfg(feature = "async-tokio")]
+        pub mod timer;
+        #[cfg(all(windows, not(feature = "forbid-unsafe")))]
+        pub mod timer_resolution;
+        #[cfg(feature = "tracing")]
+        pub mod trace;
+        #[cfg(feature = "uniffi")]
+        mod uniffi_bindings;
+        mod wait;
+
+        pub use anchor::Anchor;
+        pub use boot_timestamp::{BootTimestamp, DifferentBoot};
+        pub use budget::{Budget, SuspendPolicy};
+        pub use channel::timestamped_channel;
+        pub use clock::{Clock, ManualClock, SystemClock};
+        #[cfg(feature = "coarse")]
+        pub use coarse::coarse;
+        pub use cpu::{cpu_time_children, cpu_time_process, cpu_time_thread};
+        pub use generation::{generation, Generation};
+        #[cfg(feature = "monotonic")]
+        pub use guard::monotonic_corrections;
+        pub use lap::lap;
+        pub use pause::process_paused_time;
+        pub use retry::retry_until;
+        pub use scheduler::{CatchUp, JobHandle, Scheduler};
+        pub use session::{Invalidated, MeasurementSession};
+        pub use stopwatch::Stopwatch;
+        pub use ticker::{MissedTicks, Ticker};
+        #[cfg(feature = "suspend-events")]
+        pub use suspend_monitor::SuspendMonitor;
+        pub use wait::{sleep, sleep_until};
+        #[cfg(feature = "macros")]
+        pub use zeitstempel_macros::timed;
+    }
+}
+
 /// Returns a timestamp corresponding to "now".
 ///
 /// It can be compared to other timestamps gathered from this API, as long as the host was not
@@ -85,21 +392,290 @@ cfg_if::cfg_if! {
 /// * The clocks on some operating systems, e.g. on Windows, are not nanosecond-precise.
 ///   The value will still use nanosecond resolution.
 pub fn now() -> u64 {
-    sys::now_including_suspend()
+    let now = sys::now_including_suspend();
+    #[cfg(feature = "monotonic")]
+    let now = guard::monotonic(now);
+    #[cfg(feature = "testing")]
+    let now = test::including_suspend(now);
+    now
+}
+
+/// Returns a timestamp corresponding to "now", or why the clock couldn't be read.
+///
+/// This is the same clock as [`now`], which returns zero or the latest timestamp instead of
+/// failing, e.g. when the kernel fails to read the clock or an SGX enclave has no clock hook yet.
+/// Embedders that want to degrade gracefully, e.g. by dropping a measurement, can check
+/// the error instead.
+///
+/// ```
+/// match zeitstempel::try_now() {
+///     Ok(ts) => println!("now: {}", ts),
+///     Err(e) => println!("no timestamp: {}", e),
+/// }
+/// ```
+pub fn try_now() -> Result<u64, Error> {
+    let now = sys::try_now_including_suspend();
+    #[cfg(feature = "monotonic")]
+    let now = now.map(guard::monotonic);
+    #[cfg(feature = "testing")]
+    let now = now.map(test::including_suspend);
+    now
+}
+
+/// Returns a timestamp corresponding to "now" as a signed integer.
+///
+/// This is the same clock as [`now`], for consumers without unsigned 64-bit integers,
+/// such as Java or SQLite.
+///
+/// Values beyond `i64::MAX` saturate at `i64::MAX`.
+/// That's more than 292 years after the clock's starting point, usually the last boot.
+pub fn now_i64() -> i64 {
+    saturating_i64(now())
+}
+
+fn saturating_i64(ts: u64) -> i64 {
+    ts.try_into().unwrap_or(i64::MAX)
+}
+
+/// Returns a timestamp corresponding to "now" that does not advance while the system is
+/// suspended.
+///
+/// Timestamps from this clock must not be compared to timestamps from [`now`].
+///
+/// ## Note
+///
+/// * The difference between two timestamps will *not* include time the system was in sleep or
+///   hibernation, where the operating system allows to tell it apart.
+///   The fallback used on other operating systems returns the same clock as [`now`].
+/// * The difference between two timestamps gathered from this is in nanoseconds.
+pub fn now_excluding_suspend() -> u64 {
+    let now = sys::now_excluding_suspend();
+    #[cfg(feature = "testing")]
+    let now = test::excluding_suspend(now);
+    now
+}
+
+/// Returns readings of [`now`] and [`now_excluding_suspend`], taken as closely together as the
+/// platform allows.
+///
+/// Reading the clocks with two calls leaves an arbitrary gap between them, e.g. when the thread
+/// is preempted. Here the clock excluding suspend is read between two readings of the other one,
+/// and the tightest of a few attempts is kept. Platforms with a single clock read it once.
+///
+/// ```
+/// let readings = zeitstempel::now_both();
+/// println!("suspended for {:?} since boot", readings.suspended());
+/// ```
+pub fn now_both() -> Readings {
+    let readings = sys::now_both();
+    #[cfg(feature = "monotonic")]
+    let readings = Readings {
+        including_suspend: guard::monotonic(readings.including_suspend),
+        ..readings
+    };
+    #[cfg(feature = "testing")]
+    let readings = Readings {
+        including_suspend: test::including_suspend(readings.including_suspend),
+        excluding_suspend: test::excluding_suspend(readings.excluding_suspend),
+    };
+    readings
+}
+
+/// Returns the total time the system spent in suspend since boot.
+///
+/// It's the difference between [`now`] and [`now_excluding_suspend`], which count from boot on
+/// most systems, read with [`now_both`].
+/// Reporting it periodically tells how long a device was asleep in between.
+///
+/// ```
+/// let asleep = zeitstempel::suspended_time();
+/// println!("suspended for {:?} since boot", asleep);
+/// ```
+///
+/// ## Note
+///
+/// * Where the platform can't tell suspend time apart, e.g. with the fallback, it stays zero.
+/// * The two clocks are read one after the other, so it may be off by the time in between,
+///   usually some nanoseconds.
+pub fn suspended_time() -> Duration {
+    now_both().suspended()
 }
 
-#[cfg(test)]
-mod test {
+/// Returns a timestamp corresponding to "now", in whole milliseconds.
+///
+/// This is the same clock as [`now`], truncated.
+/// It's converted straight from the platform's native unit,
+/// so on platforms with a millisecond clock the value is never scaled up and down.
+/// Like [`raw_now`], it isn't held back by the `monotonic` feature.
+pub fn now_ms() -> u64 {
+    raw_now().to_millis()
+}
+
+/// Returns a timestamp corresponding to "now", in whole microseconds.
+///
+/// This is the same clock as [`now`], truncated, like [`now_ms`].
+pub fn now_us() -> u64 {
+    raw_now().to_micros()
+}
+
+/// Returns which clock [`now`] reads on this system, and how precise it is.
+///
+/// Where the best clock may be missing at runtime, it's probed on first use and the result is
+/// cached: on Linux `CLOCK_BOOTTIME` falls back to `CLOCK_MONOTONIC`,
+/// on Windows `QueryInterruptTime` to `GetTickCount64`,
+/// in browsers `performance.now()` to `Date.now()`.
+/// This reports the clock that was picked.
+pub fn clock_info() -> ClockInfo {
+    sys::clock_info()
+}
+
+/// Returns a timestamp corresponding to "now" in the platform's native unit.
+///
+/// This is the same clock as [`now`], without converting the value to nanoseconds:
+/// 100 nanosecond units on Windows, Mach ticks on macOS and iOS, nanoseconds elsewhere.
+/// Use [`RawTimestamp::to_nanos`] to convert it later.
+///
+/// The `monotonic` feature doesn't apply here: the reading is returned as the platform
+/// produced it, so every raw timestamp is in the same unit and can be converted later in bulk.
+pub fn raw_now() -> RawTimestamp {
+    #[cfg(feature = "testing")]
+    if test::is_active() {
+        let unit = sys::tick_unit();
+        return RawTimestamp {
+            value: unit.ticks_in(now()),
+            unit,
+        };
+    }
+    sys::raw_now_including_suspend()
+}
+
+/// Returns a timestamp corresponding to "now" in ticks of the platform's native unit.
+///
+/// This is the value of [`raw_now`] without its unit, which is the same on every call:
+/// divide by [`ticks_per_second`] to convert it.
+/// Useful for archiving many timestamps cheaply and converting them later.
+pub fn now_raw() -> u64 {
+    raw_now().value
+}
+
+/// Returns how many ticks of [`raw_now`] and [`now_raw`] make up a second on this platform.
+///
+/// Together with the tick values this converts raw timestamps exactly,
+/// e.g. 10,000,000 on Windows and 24,000,000 for Mach ticks on Apple Silicon.
+/// It doesn't change while the process runs, so it can be read once and stored.
+pub fn ticks_per_second() -> u64 {
+    sys::tick_unit().ticks_per_second()
+}
+
+/// Returns the length of one tick of [`raw_now`] and [`now_raw`] on this platform,
+/// rounded down to whole nanoseconds.
+pub fn tick_duration() -> Duration {
+    sys::tick_unit().tick_duration()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
     use super::*;
     use std::thread;
     use std::time::Duration;
 
+    #[test]
+    fn raw_ticks() {
+        let raw = raw_now();
+        assert_eq!(sys::tick_unit(), raw.unit);
+        let nanos = u128::from(raw.value) * 1_000_000_000 / u128::from(ticks_per_second());
+        assert_eq!(raw.to_nanos() as u128, nanos);
+        assert!(tick_duration() <= Duration::from_millis(1));
+
+        let before = raw.value;
+        let ticks = now_raw();
+        assert!(before <= ticks && ticks <= raw_now().value);
+    }
+
     #[test]
     fn order() {
-        let ts1 = now();
+        let start = now_both();
+        thread::sleep(Duration::from_millis(2));
+
+        assert_elapsed!(start, > Duration::from_secs(0));
+    }
+
+    #[test]
+    fn order_excluding_suspend() {
+        let start = Instant::now_excluding_suspend();
         thread::sleep(Duration::from_millis(2));
-        let ts2 = now();
 
-        assert!(ts1 < ts2);
+        assert_elapsed!(start, > Duration::from_secs(0));
+    }
+
+    #[test]
+    fn suspended_time_since_boot() {
+        let suspended = suspended_time();
+        assert!(suspended <= Duration::from_nanos(now()));
+    }
+
+    #[test]
+    fn try_now_reads_same_clock() {
+        let before = now();
+        let ts = try_now().unwrap();
+        assert!(before <= ts && ts <= now());
+    }
+
+    #[test]
+    fn clock_info_names_clock() {
+        let info = clock_info();
+        assert!(!info.name.is_empty());
+        if cfg!(target_os = "linux") {
+            assert!(info.resolution.is_some());
+        }
+        if cfg!(all(
+            target_os = "linux",
+            not(any(feature = "tsc", feature = "s390x-tod"))
+        )) {
+            assert_eq!("CLOCK_BOOTTIME", info.name);
+        }
+    }
+
+    #[test]
+    fn i64_saturates() {
+        assert_eq!(17, saturating_i64(17));
+        assert_eq!(i64::MAX, saturating_i64(i64::MAX as u64));
+        assert_eq!(i64::MAX, saturating_i64(u64::MAX));
+        assert!(now_i64() > 0);
+    }
+
+    #[test]
+    fn truncated() {
+        let before = now();
+        let (ms, us) = (now_ms(), now_us());
+        let after = now();
+
+        assert!(before / 1_000_000 <= ms && ms <= after / 1_000_000);
+        assert!(before / 1_000 <= us && us <= after / 1_000);
+    }
+
+    #[test]
+    fn raw_matches_now() {
+        let before = now();
+        let raw = raw_now().to_nanos();
+        let after = now();
+
+        assert!(before <= raw && raw <= after);
+    }
+}
+
+/// Proofs that the conversion never panics, checked with `cargo kani`.
+#[cfg(kani)]
+mod verification {
+    use super::*;
+
+    #[kani::proof]
+    fn saturating_i64_never_panics() {
+        let ts: u64 = kani::any();
+        let signed = saturating_i64(ts);
+        assert!(signed >= 0);
+        if ts <= i64::MAX as u64 {
+            assert_eq!(ts, signed as u64);
+        }
     }
 }
@@ -1,9 +1,29 @@
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::{bracketed, Readings};
+
 const NS_PER_S: u64 = 1_000_000_000;
 
+#[cfg(not(feature = "forbid-unsafe"))]
 fn timespec_to_ns(ts: libc::timespec) -> u64 {
     (ts.tv_sec as u64) * NS_PER_S + (ts.tv_nsec as u64)
 }
 
+/// The clock behind [`now_including_suspend`], probed once.
+///
+/// `CLOCK_BOOTTIME` exists since Linux 2.6.39. Binaries running on older kernels, or under
+/// sandboxes filtering it, fall back to `CLOCK_MONOTONIC`, which doesn't count suspend time.
+static INCLUDING: Lazy<libc::clockid_t> =
+    Lazy::new(|| match try_clock_gettime(libc::CLOCK_BOOTTIME) {
+        Ok(_) => libc::CLOCK_BOOTTIME,
+        Err(_) => libc::CLOCK_MONOTONIC,
+    });
+
 /// The time from a clock that cannot be set
 /// and represents monotonic time since some unspecified starting point,
 /// that also includes any time that the system is suspended.
@@ -12,13 +32,121 @@ fn timespec_to_ns(ts: libc::timespec) -> u64 {
 ///
 /// [`clock_gettime`]: https://manpages.debian.org/buster/manpages-dev/clock_gettime.3.en.html
 pub fn now_including_suspend() -> u64 {
+    clock_gettime(*INCLUDING)
+}
+
+/// Like [`now_including_suspend`], but reports when the kernel fails to read the clock.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    try_clock_gettime(*INCLUDING)
+}
+
+/// The time from a clock that cannot be set
+/// and represents monotonic time since some unspecified starting point,
+/// that does not count time that the system is suspended.
+///
+/// See [`clock_gettime`].
+///
+/// [`clock_gettime`]: https://manpages.debian.org/buster/manpages-dev/clock_gettime.3.en.html
+pub fn now_excluding_suspend() -> u64 {
+    clock_gettime(libc::CLOCK_MONOTONIC)
+}
+
+/// Reads both clocks back-to-back.
+pub fn now_both() -> Readings {
+    bracketed(now_including_suspend, now_excluding_suspend)
+}
+
+/// How far `clock` is behind `CLOCK_BOOTTIME`, in nanoseconds.
+///
+/// For `CLOCK_MONOTONIC` that's the time spent in suspend since boot.
+pub(crate) fn offset_to(clock: libc::clockid_t) -> u64 {
+    let other = clock_gettime(clock);
+    now_including_suspend().saturating_sub(other)
+}
+
+/// Reads `clock`, or zero if that fails.
+pub(crate) fn clock_gettime(clock: libc::clockid_t) -> u64 {
+    try_clock_gettime(clock).unwrap_or(0)
+}
+
+#[cfg(not(feature = "forbid-unsafe"))]
+fn try_clock_gettime(clock: libc::clockid_t) -> Result<u64, Error> {
     let mut ts = libc::timespec {
         tv_sec: 0,
         tv_nsec: 0,
     };
-    unsafe {
-        libc::clock_gettime(libc::CLOCK_BOOTTIME, &mut ts);
+    let ret = unsafe { libc::clock_gettime(clock, &mut ts) };
+    if ret != 0 {
+        return match std::io::Error::last_os_error().raw_os_error() {
+            Some(libc::EINVAL) => Err(Error::Unavailable),
+            Some(code) => Err(Error::Os(code)),
+            None => Err(Error::Unavailable),
+        };
     }
 
-    timespec_to_ns(ts)
+    Ok(timespec_to_ns(ts))
+}
+
+/// Reads the clock through rustix's safe wrapper,
+/// which only fails for clocks it doesn't know.
+#[cfg(feature = "forbid-unsafe")]
+fn try_clock_gettime(clock: libc::clockid_t) -> Result<u64, Error> {
+    use std::convert::TryFrom;
+
+    use rustix::time::ClockId;
+
+    let clock = ClockId::try_from(clock).map_err(|_| Error::Unavailable)?;
+    let ts = rustix::time::clock_gettime(clock);
+    Ok((ts.tv_sec as u64) * NS_PER_S + (ts.tv_nsec as u64))
+}
+
+/// The same clock as [`now_including_suspend`], which counts nanoseconds already.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: now_including_suspend(),
+        unit: TickUnit::Nanoseconds,
+    }
+}
+
+/// [`raw_now_including_suspend`] counts nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// The probed clock, with the resolution reported by `clock_getres`.
+pub fn clock_info() -> ClockInfo {
+    let clock = *INCLUDING;
+    ClockInfo {
+        name: if clock == libc::CLOCK_BOOTTIME {
+            "CLOCK_BOOTTIME"
+        } else {
+            "CLOCK_MONOTONIC"
+        },
+        resolution: clock_getres(clock),
+        includes_suspend: clock == libc::CLOCK_BOOTTIME,
+    }
+}
+
+#[cfg(not(feature = "forbid-unsafe"))]
+fn clock_getres(clock: libc::clockid_t) -> Option<Duration> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    let ret = unsafe { libc::clock_getres(clock, &mut ts) };
+    if ret != 0 {
+        return None;
+    }
+
+    Some(Duration::from_nanos(timespec_to_ns(ts)))
+}
+
+#[cfg(feature = "forbid-unsafe")]
+fn clock_getres(clock: libc::clockid_t) -> Option<Duration> {
+    use std::convert::TryFrom;
+
+    use rustix::time::ClockId;
+
+    let ts = rustix::time::clock_getres(ClockId::try_from(clock).ok()?);
+    Some(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
 }
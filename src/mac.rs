@@ -1,10 +1,36 @@
+//! Timestamp implementation for macOS, iOS and Mac Catalyst.
+//!
+//! `clock_gettime_nsec_np` and `mach_continuous_time` are available since macOS 10.12 and
+//! iOS 10. Mac Catalyst (`*-apple-ios-macabi`) builds are iOS builds running on macOS 10.15+,
+//! so they use this implementation as well.
+
+use std::time::Duration;
+
 use libc::clockid_t;
+use once_cell::sync::Lazy;
+
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::{bracketed, Readings};
+
+#[repr(C)]
+#[derive(Default)]
+struct MachTimebaseInfo {
+    numer: u32,
+    denom: u32,
+}
 
 extern "C" {
     fn clock_gettime_nsec_np(clock_id: clockid_t) -> u64;
+    fn mach_continuous_time() -> u64;
+    #[cfg(feature = "coarse")]
+    fn mach_continuous_approximate_time() -> u64;
+    fn mach_timebase_info(info: *mut MachTimebaseInfo) -> libc::c_int;
 }
 
 const CLOCK_MONOTONIC_RAW: clockid_t = 4;
+const CLOCK_UPTIME_RAW: clockid_t = 8;
 
 /// The time from a clock that increments monotonically,
 /// tracking the time since an arbitrary point.
@@ -15,3 +41,78 @@ const CLOCK_MONOTONIC_RAW: clockid_t = 4;
 pub fn now_including_suspend() -> u64 {
     unsafe { clock_gettime_nsec_np(CLOCK_MONOTONIC_RAW) }
 }
+
+/// `clock_gettime_nsec_np` can't fail for clocks that exist.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    Ok(now_including_suspend())
+}
+
+/// The time from a clock that increments monotonically,
+/// tracking the time since an arbitrary point,
+/// that does not increment while the system is asleep.
+///
+/// See [`clock_gettime_nsec_np`].
+///
+/// [`clock_gettime_nsec_np`]: https://opensource.apple.com/source/Libc/Libc-1158.1.2/gen/clock_gettime.3.auto.html
+pub fn now_excluding_suspend() -> u64 {
+    unsafe { clock_gettime_nsec_np(CLOCK_UPTIME_RAW) }
+}
+
+/// Reads both clocks back-to-back.
+pub fn now_both() -> Readings {
+    bracketed(now_including_suspend, now_excluding_suspend)
+}
+
+/// The length of a Mach tick, which never changes while the system is running.
+static TIMEBASE: Lazy<TickUnit> = Lazy::new(|| {
+    let mut info = MachTimebaseInfo::default();
+    unsafe {
+        mach_timebase_info(&mut info);
+    }
+
+    TickUnit::Timebase {
+        numer: info.numer,
+        denom: info.denom,
+    }
+});
+
+/// The Mach ticks backing [`now_including_suspend`].
+///
+/// See [`mach_continuous_time`].
+///
+/// [`mach_continuous_time`]: https://developer.apple.com/documentation/kernel/1646199-mach_continuous_time
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: unsafe { mach_continuous_time() },
+        unit: *TIMEBASE,
+    }
+}
+
+/// Mach ticks, in the timebase reported by `mach_timebase_info`.
+pub fn tick_unit() -> TickUnit {
+    *TIMEBASE
+}
+
+/// The same clock as [`now_including_suspend`], based on the tick count of the last context
+/// switch or interrupt instead of reading the hardware counter.
+///
+/// See [`mach_continuous_approximate_time`].
+///
+/// [`mach_continuous_approximate_time`]: https://developer.apple.com/documentation/kernel/1646200-mach_continuous_approximate_time
+#[cfg(feature = "coarse")]
+pub(crate) fn coarse_now_including_suspend() -> u64 {
+    TIMEBASE.to_nanos(unsafe { mach_continuous_approximate_time() })
+}
+
+/// `CLOCK_MONOTONIC_RAW` advances with the Mach ticks.
+pub fn clock_info() -> ClockInfo {
+    let tick = RawTimestamp {
+        value: 1,
+        unit: *TIMEBASE,
+    };
+    ClockInfo {
+        name: "CLOCK_MONOTONIC_RAW",
+        resolution: Some(Duration::from_nanos(tick.to_nanos().max(1))),
+        includes_suspend: true,
+    }
+}
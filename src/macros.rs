@@ -0,0 +1,214 @@
+//! Exported macros and the functions supporting them.
+
+use core::fmt;
+use core::time::Duration;
+
+use crate::instant::{ExcludingSuspend, IncludingSuspend, Instant};
+use crate::readings::Readings;
+
+/// A start of [`assert_elapsed!`](crate::assert_elapsed).
+pub trait Start {
+    /// Measures the time elapsed since the start.
+    fn elapsed(self) -> Elapsed;
+}
+
+/// The time elapsed since a [`Start`], with what's known about suspend.
+#[derive(Debug, Clone, Copy)]
+pub struct Elapsed {
+    elapsed: Duration,
+    clock: &'static str,
+    since: u64,
+    suspended: Suspended,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Suspended {
+    /// A plain timestamp doesn't tell.
+    Unknown,
+    /// Measured on the suspend-excluding clock.
+    NotCounted,
+    Measured(Duration),
+}
+
+impl Elapsed {
+    fn including(since: u64, suspended: Suspended) -> Elapsed {
+        Elapsed {
+            elapsed: Duration::from_nanos(crate::now().saturating_sub(since)),
+            clock: "now(), including time spent in suspend",
+            since,
+            suspended,
+        }
+    }
+
+    /// The elapsed time.
+    pub fn duration(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+impl fmt::Display for Suspended {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Suspended::Unknown => f.write_str("unknown, start from now_both() to measure it"),
+            Suspended::NotCounted => f.write_str("not counted"),
+            Suspended::Measured(suspended) => write!(f, "{:?}", suspended),
+        }
+    }
+}
+
+/// A timestamp from [`now`](crate::now).
+impl Start for u64 {
+    fn elapsed(self) -> Elapsed {
+        Elapsed::including(self, Suspended::Unknown)
+    }
+}
+
+/// Readings from [`now_both`](crate::now_both), measured on the suspend-including clock.
+impl Start for Readings {
+    fn elapsed(self) -> Elapsed {
+        let now = crate::now_both();
+        let since = self.including_suspend;
+        Elapsed {
+            elapsed: Duration::from_nanos(now.including_suspend.saturating_sub(since)),
+            clock: "now(), including time spent in suspend",
+            since,
+            suspended: Suspended::Measured(now.suspended().saturating_sub(self.suspended())),
+        }
+    }
+}
+
+impl Start for Instant<IncludingSuspend> {
+    fn elapsed(self) -> Elapsed {
+        Elapsed::including(self.as_timestamp(), Suspended::Unknown)
+    }
+}
+
+impl Start for Instant<ExcludingSuspend> {
+    fn elapsed(self) -> Elapsed {
+        let since = self.as_timestamp();
+        Elapsed {
+            elapsed: Duration::from_nanos(crate::now_excluding_suspend().saturating_sub(since)),
+            clock: "now_excluding_suspend()",
+            since,
+            suspended: Suspended::NotCounted,
+        }
+    }
+}
+
+/// Panics with the failure message of [`assert_elapsed!`](crate::assert_elapsed).
+#[cold]
+#[track_caller]
+pub fn elapsed_failed(op: &str, elapsed: Elapsed, expected: Duration) -> ! {
+    panic!(
+        "assertion failed: `elapsed {} expected`\n  elapsed: {:?}\n expected: {:?}\n    clock: {}, since {}\nsuspended: {}",
+        op, elapsed.elapsed, expected, elapsed.clock, elapsed.since, elapsed.suspended
+    )
+}
+
+/// Asserts that the time elapsed since `start` compares to a duration as expected.
+///
+/// `start` is one of
+///
+/// * a timestamp from [`now`](crate::now), measured on the same clock, so the elapsed time
+///   includes time spent in suspend,
+/// * readings from [`now_both`](crate::now_both), measured the same way, which also tell how
+///   much of the elapsed time the system was suspended,
+/// * an [`Instant`](crate::Instant) of either clock, measured on its own clock.
+///
+/// Supported comparisons are `>=`, `>`, `<=`, `<`, `==` and `!=`.
+///
+/// On failure the message shows the elapsed and the expected duration, the clock measured on
+/// and how much of the elapsed time was spent in suspend, as far as `start` tells.
+///
+/// # Example
+///
+/// ```
+/// # use std::{thread, time::Duration};
+/// use zeitstempel::assert_elapsed;
+///
+/// let start = zeitstempel::now();
+/// thread::sleep(Duration::from_millis(2));
+///
+/// assert_elapsed!(start, >= Duration::from_millis(2));
+/// assert_elapsed!(start, < Duration::from_secs(60));
+///
+/// let start = zeitstempel::now_both();
+/// assert_elapsed!(start, < Duration::from_secs(60));
+/// ```
+#[macro_export]
+macro_rules! assert_elapsed {
+    (@check $start:expr, $op:tt, $expected:expr) => {{
+        let elapsed = $crate::__private::Start::elapsed($start);
+        let expected: ::core::time::Duration = $expected;
+        if !(elapsed.duration() $op expected) {
+            $crate::__private::elapsed_failed(stringify!($op), elapsed, expected);
+        }
+    }};
+    ($start:expr, >= $expected:expr $(,)?) => {
+        $crate::assert_elapsed!(@check $start, >=, $expected)
+    };
+    ($start:expr, > $expected:expr $(,)?) => {
+        $crate::assert_elapsed!(@check $start, >, $expected)
+    };
+    ($start:expr, <= $expected:expr $(,)?) => {
+        $crate::assert_elapsed!(@check $start, <=, $expected)
+    };
+    ($start:expr, < $expected:expr $(,)?) => {
+        $crate::assert_elapsed!(@check $start, <, $expected)
+    };
+    ($start:expr, == $expected:expr $(,)?) => {
+        $crate::assert_elapsed!(@check $start, ==, $expected)
+    };
+    ($start:expr, != $expected:expr $(,)?) => {
+        $crate::assert_elapsed!(@check $start, !=, $expected)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "elapsed < expected")]
+    fn failure_message() {
+        let start = crate::now();
+        assert_elapsed!(start, < Duration::from_nanos(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "clock: now(), including time spent in suspend")]
+    fn failure_names_clock() {
+        let start = crate::now();
+        assert_elapsed!(start, == Duration::from_secs(60));
+    }
+
+    #[test]
+    #[should_panic(expected = "suspended: unknown")]
+    fn failure_without_suspend_time() {
+        let start = crate::now();
+        assert_elapsed!(start, > Duration::from_secs(60));
+    }
+
+    #[test]
+    #[should_panic(expected = "clock: now_excluding_suspend(), since")]
+    fn failure_names_excluding_clock() {
+        let start = crate::Instant::now_excluding_suspend();
+        assert_elapsed!(start, > Duration::from_secs(60));
+    }
+
+    #[test]
+    fn measures_suspend_time() {
+        let elapsed = Start::elapsed(crate::now_both());
+        assert!(matches!(elapsed.suspended, Suspended::Measured(_)));
+        assert!(elapsed.duration() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn comparisons() {
+        let start = crate::now();
+        assert_elapsed!(start, >= Duration::from_nanos(0));
+        assert_elapsed!(start, < Duration::from_secs(60),);
+        assert_elapsed!(start, <= Duration::from_secs(60));
+        assert_elapsed!(start, != Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,108 @@
+//! Recording durations into [metrics] histograms, available with the `metrics` feature.
+//!
+//! `metrics` has no clock of its own: durations are measured by the caller and recorded as
+//! seconds. Measured with [`now`](crate::now), they include the time the system spent
+//! suspended, so a request in flight while a daemon's host slept isn't recorded as fast.
+//!
+//! ```
+//! use zeitstempel::metrics_timer::HistogramTimer;
+//!
+//! let timer = HistogramTimer::start(metrics::histogram!("upload_duration_seconds"));
+//! // upload ...
+//! timer.stop();
+//! ```
+//!
+//! [metrics]: https://docs.rs/metrics
+
+use std::time::Duration;
+
+use metrics::Histogram;
+
+/// Records the time since it was started into a histogram, when stopped or dropped.
+#[derive(Debug)]
+#[must_use = "dropping the timer records the duration immediately"]
+pub struct HistogramTimer {
+    /// Taken when the duration is recorded.
+    histogram: Option<Histogram>,
+    start: u64,
+}
+
+impl HistogramTimer {
+    /// Starts timing for `histogram`.
+    pub fn start(histogram: Histogram) -> HistogramTimer {
+        HistogramTimer {
+            histogram: Some(histogram),
+            start: crate::now(),
+        }
+    }
+
+    /// How long the timer has been running.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(crate::now().saturating_sub(self.start))
+    }
+
+    /// Records the duration and returns it.
+    pub fn stop(mut self) -> Duration {
+        self.record()
+    }
+
+    fn record(&mut self) -> Duration {
+        let elapsed = self.elapsed();
+        if let Some(histogram) = self.histogram.take() {
+            histogram.record(elapsed);
+        }
+        elapsed
+    }
+}
+
+impl Drop for HistogramTimer {
+    fn drop(&mut self) {
+        self.record();
+    }
+}
+
+/// Runs `f` and records how long it took into `histogram`.
+pub fn time<T>(histogram: &Histogram, f: impl FnOnce() -> T) -> T {
+    let start = crate::now();
+    let result = f();
+    record_since(histogram, start);
+    result
+}
+
+/// Records the time since `start`, a timestamp from [`now`](crate::now), into `histogram`.
+pub fn record_since(histogram: &Histogram, start: u64) {
+    histogram.record(Duration::from_nanos(crate::now().saturating_sub(start)));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    #[derive(Default)]
+    struct Values(Mutex<Vec<f64>>);
+
+    impl metrics::HistogramFn for Values {
+        fn record(&self, value: f64) {
+            self.0.lock().unwrap().push(value);
+        }
+    }
+
+    #[test]
+    fn records_seconds() {
+        let values = Arc::new(Values::default());
+        let histogram = Histogram::from_arc(Arc::clone(&values));
+
+        let timer = HistogramTimer::start(histogram.clone());
+        thread::sleep(Duration::from_millis(2));
+        let elapsed = timer.stop();
+        time(&histogram, || ());
+        drop(HistogramTimer::start(histogram));
+
+        let values = values.0.lock().unwrap();
+        assert_eq!(3, values.len());
+        assert_eq!(elapsed.as_secs_f64(), values[0]);
+        assert!(values[0] >= 0.002);
+    }
+}
@@ -0,0 +1,208 @@
+//! Estimating the offset between the clocks of two processes, NTP style.
+//!
+//! Timestamps from [`now`](crate::now) count from an unspecified point, usually boot,
+//! so they can't be compared between machines, or between a host and a container with its
+//! own boot epoch. An exchange of four timestamps over any channel measures the difference:
+//!
+//! 1. The local side creates a [`Request`], stamped with its send time, and sends it.
+//! 2. The remote side answers with [`Response::answer`], stamping the receive and send times.
+//! 3. The local side passes the response to [`OffsetEstimator::receive`], stamping its
+//!    receive time.
+//!
+//! Messages convert to and from bytes for sending them over sockets, pipes or shared memory.
+//!
+//! ```
+//! use zeitstempel::offset::{OffsetEstimator, Request, Response};
+//!
+//! let mut estimator = OffsetEstimator::new();
+//! for _ in 0..8 {
+//!     let request = estimator.request();
+//!     // In reality, sent to the other process, which replies with:
+//!     let response = Response::answer(Request::from_bytes(request.to_bytes()));
+//!     estimator.receive(Response::from_bytes(response.to_bytes()));
+//! }
+//!
+//! let offset = estimator.estimate().unwrap();
+//! assert!(offset.offset.abs() < 1_000_000_000);
+//! ```
+
+use std::convert::TryInto;
+use std::time::Duration;
+
+/// The first message of an exchange, carrying the local send time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Request {
+    sent: u64,
+}
+
+impl Request {
+    /// Encodes the request as 8 little-endian bytes.
+    pub fn to_bytes(self) -> [u8; 8] {
+        self.sent.to_le_bytes()
+    }
+
+    /// Decodes a request from [`Request::to_bytes`].
+    pub fn from_bytes(bytes: [u8; 8]) -> Request {
+        Request {
+            sent: u64::from_le_bytes(bytes),
+        }
+    }
+}
+
+/// The remote side's answer to a [`Request`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Response {
+    request_sent: u64,
+    received: u64,
+    sent: u64,
+}
+
+impl Response {
+    /// Answers a request that was received just now.
+    pub fn answer(request: Request) -> Response {
+        let now = crate::now();
+        Response::answer_received_at(request, now)
+    }
+
+    /// Answers a request that was received at `received`, a timestamp from [`now`](crate::now).
+    ///
+    /// Stamping the receive time as early as possible, e.g. right after reading from a socket,
+    /// and answering later keeps the processing time out of the estimate.
+    pub fn answer_received_at(request: Request, received: u64) -> Response {
+        Response {
+            request_sent: request.sent,
+            received,
+            sent: crate::now(),
+        }
+    }
+
+    /// Encodes the response as 24 little-endian bytes.
+    pub fn to_bytes(self) -> [u8; 24] {
+        let mut bytes = [0; 24];
+        bytes[..8].copy_from_slice(&self.request_sent.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.received.to_le_bytes());
+        bytes[16..].copy_from_slice(&self.sent.to_le_bytes());
+        bytes
+    }
+
+    /// Decodes a response from [`Response::to_bytes`].
+    pub fn from_bytes(bytes: [u8; 24]) -> Response {
+        let field = |range: std::ops::Range<usize>| {
+            u64::from_le_bytes(bytes[range].try_into().unwrap_or_default())
+        };
+        Response {
+            request_sent: field(0..8),
+            received: field(8..16),
+            sent: field(16..24),
+        }
+    }
+}
+
+/// The estimated difference between the remote and the local clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Offset {
+    /// How far the remote clock is ahead of the local one, in nanoseconds.
+    /// Negative if it's behind.
+    pub offset: i64,
+    /// How far off the estimate can be at most, half the round trip time of the exchange
+    /// it was measured with.
+    pub uncertainty: Duration,
+}
+
+impl Offset {
+    /// Converts a local timestamp to the remote clock, saturating at its bounds.
+    pub fn to_remote(&self, local: u64) -> u64 {
+        shift(local, i128::from(self.offset))
+    }
+
+    /// Converts a remote timestamp to the local clock, saturating at its bounds.
+    pub fn to_local(&self, remote: u64) -> u64 {
+        shift(remote, -i128::from(self.offset))
+    }
+}
+
+fn shift(ts: u64, by: i128) -> u64 {
+    (i128::from(ts) + by).clamp(0, i128::from(u64::MAX)) as u64
+}
+
+/// Collects exchanges and estimates the offset from the most precise one.
+///
+/// Queueing delays only ever make a round trip longer, so like NTP's clock filter the
+/// estimator uses the exchange with the shortest round trip time.
+#[derive(Debug, Default)]
+pub struct OffsetEstimator {
+    best: Option<Offset>,
+    exchanges: usize,
+}
+
+impl OffsetEstimator {
+    /// Creates an estimator without any exchanges.
+    pub fn new() -> OffsetEstimator {
+        OffsetEstimator::default()
+    }
+
+    /// Starts an exchange: the request to send to the remote side.
+    pub fn request(&self) -> Request {
+        Request { sent: crate::now() }
+    }
+
+    /// Completes an exchange with the remote side's response, received just now.
+    ///
+    /// Returns the offset measured by this exchange alone.
+    pub fn receive(&mut self, response: Response) -> Offset {
+        let received = i128::from(crate::now());
+        let (t1, t2, t3) = (
+            i128::from(response.request_sent),
+            i128::from(response.received),
+            i128::from(response.sent),
+        );
+
+        let offset = ((t2 - t1) + (t3 - received)) / 2;
+        let round_trip = ((received - t1) - (t3 - t2)).max(0);
+        let offset = Offset {
+            offset: offset.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64,
+            uncertainty: Duration::from_nanos((round_trip / 2).min(i128::from(u64::MAX)) as u64),
+        };
+
+        self.exchanges += 1;
+        match self.best {
+            Some(best) if best.uncertainty <= offset.uncertainty => {}
+            _ => self.best = Some(offset),
+        }
+        offset
+    }
+
+    /// Returns the best estimate so far, `None` before the first exchange.
+    pub fn estimate(&self) -> Option<Offset> {
+        self.best
+    }
+
+    /// Returns how many exchanges were completed.
+    pub fn exchanges(&self) -> usize {
+        self.exchanges
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn estimates_shifted_clock() {
+        let shift = 3_600_000_000_000;
+        let mut estimator = OffsetEstimator::new();
+        for _ in 0..4 {
+            let request = estimator.request();
+            let mut response = Response::answer(request);
+            response.received += shift;
+            response.sent += shift;
+            estimator.receive(response);
+        }
+
+        let offset = estimator.estimate().unwrap();
+        assert_eq!(4, estimator.exchanges());
+        let error = (offset.offset - shift as i64).unsigned_abs();
+        assert!(Duration::from_nanos(error) <= offset.uncertainty + Duration::from_millis(1));
+        assert_eq!(1_000, offset.to_local(offset.to_remote(1_000)));
+    }
+}
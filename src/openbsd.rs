@@ -0,0 +1,60 @@
+//! Timestamp implementation for OpenBSD.
+//!
+//! `CLOCK_BOOTTIME`, available since OpenBSD 6.3, keeps counting while the system is
+//! suspended. `CLOCK_UPTIME` doesn't.
+
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::posix;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::{bracketed, Readings};
+
+/// The time from `CLOCK_BOOTTIME`, or zero if it can't be read.
+///
+/// See [`clock_gettime`].
+///
+/// [`clock_gettime`]: https://man.openbsd.org/clock_gettime.2
+pub fn now_including_suspend() -> u64 {
+    try_now_including_suspend().unwrap_or(0)
+}
+
+/// Reports when the kernel fails to read `CLOCK_BOOTTIME`.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    posix::clock_gettime(libc::CLOCK_BOOTTIME)
+}
+
+/// The time from `CLOCK_UPTIME`, or zero if it can't be read.
+///
+/// See [`clock_gettime`].
+///
+/// [`clock_gettime`]: https://man.openbsd.org/clock_gettime.2
+pub fn now_excluding_suspend() -> u64 {
+    posix::clock_gettime(libc::CLOCK_UPTIME).unwrap_or(0)
+}
+
+/// Reads both clocks back-to-back.
+pub fn now_both() -> Readings {
+    bracketed(now_including_suspend, now_excluding_suspend)
+}
+
+/// The same clock as [`now_including_suspend`], which counts nanoseconds already.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: now_including_suspend(),
+        unit: TickUnit::Nanoseconds,
+    }
+}
+
+/// [`raw_now_including_suspend`] counts nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// `CLOCK_BOOTTIME`, with the resolution reported by `clock_getres`.
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        name: "CLOCK_BOOTTIME",
+        resolution: posix::clock_getres(libc::CLOCK_BOOTTIME),
+        includes_suspend: true,
+    }
+}
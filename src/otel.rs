@@ -0,0 +1,76 @@
+//! Span timestamps for OpenTelemetry, available with the `opentelemetry` feature.
+//!
+//! OTLP wants span start and end times as nanoseconds since the Unix epoch.
+//! Reading the wall clock for each of them makes spans inconsistent:
+//! adjustments to the system time show up as negative or inflated span durations.
+//!
+//! Instead, timestamps are taken from [`now`](crate::now) and converted to the epoch through
+//! a single anchor, a pair of clock and wall-clock readings taken once per process.
+//! Span durations then exactly match the elapsed time, including time spent in suspend.
+//!
+//! ```
+//! use opentelemetry::trace::{Span, Tracer};
+//! # let tracer = opentelemetry::trace::noop::NoopTracer::new();
+//!
+//! let mut span = zeitstempel::otel::span_builder("upload").start(&tracer);
+//! // ...
+//! zeitstempel::otel::end_span(&mut span);
+//! ```
+
+use std::borrow::Cow;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use opentelemetry::trace::{Span, SpanBuilder};
+
+use crate::boot;
+
+/// A clock reading and the wall clock at the same moment, in nanoseconds since the Unix epoch.
+static ANCHOR: Lazy<(u64, u64)> = Lazy::new(|| (crate::now(), boot::wall_clock_now()));
+
+/// Converts a timestamp from [`now`](crate::now) to nanoseconds since the Unix epoch,
+/// as used by OTLP.
+pub fn to_epoch_nanos(ts: u64) -> u64 {
+    let (anchor, anchor_epoch) = *ANCHOR;
+    if ts >= anchor {
+        anchor_epoch.saturating_add(ts - anchor)
+    } else {
+        anchor_epoch.saturating_sub(anchor - ts)
+    }
+}
+
+/// Converts a timestamp from [`now`](crate::now) to a [`SystemTime`],
+/// as used by the OpenTelemetry API.
+pub fn to_system_time(ts: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_nanos(to_epoch_nanos(ts))
+}
+
+/// Returns "now" as a [`SystemTime`] suitable for span start and end times.
+pub fn now() -> SystemTime {
+    to_system_time(crate::now())
+}
+
+/// Creates a span builder with its start time set to "now".
+pub fn span_builder<T: Into<Cow<'static, str>>>(name: T) -> SpanBuilder {
+    SpanBuilder::from_name(name).with_start_time(now())
+}
+
+/// Ends a span "now".
+pub fn end_span<S: Span>(span: &mut S) {
+    span.end_with_timestamp(now());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn consistent_durations() {
+        let start = crate::now();
+        let end = start + 1_500_000_000;
+
+        let duration = to_system_time(end).duration_since(to_system_time(start));
+        assert_eq!(Duration::from_millis(1500), duration.unwrap());
+        assert_eq!(1_500_000_000, to_epoch_nanos(end) - to_epoch_nanos(start));
+    }
+}
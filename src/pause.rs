@@ -0,0 +1,194 @@
+//! Detecting when the process itself was stopped or frozen, unlike the whole system.
+//!
+//! A background thread wakes up regularly and samples the suspend-excluding clock together with
+//! the process's CPU time. When it wakes up late, the process either wasn't running at all or
+//! was running without the detector, e.g. busy on other threads or throttled to its cgroup's CPU
+//! quota. The CPU time tells them apart: while stopped the process makes no progress, so the
+//! part of the gap that the CPU time can't account for is counted as paused.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+/// How often the detector thread checks in.
+const INTERVAL: Duration = Duration::from_millis(50);
+
+/// Pauses this short are still attributed to scheduling delays.
+const TOLERANCE: Duration = Duration::from_millis(50);
+
+/// The total pause time detected, in nanoseconds.
+static PAUSED: AtomicU64 = AtomicU64::new(0);
+
+static DETECTOR: Lazy<()> = Lazy::new(|| {
+    let spawned = thread::Builder::new()
+        .name("zeitstempel-pause-detector".into())
+        .spawn(|| {
+            let mut detector = Detector::new(sample());
+            loop {
+                thread::sleep(INTERVAL);
+                PAUSED.fetch_add(detector.observe(sample()), Ordering::Relaxed);
+            }
+        });
+    // Without the thread nothing is detected, which is reported as no pauses.
+    drop(spawned);
+});
+
+/// A reading of the suspend-excluding clock and the process's CPU time, in nanoseconds.
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    at: u64,
+    cpu: Option<u64>,
+}
+
+fn sample() -> Sample {
+    Sample {
+        at: crate::now_excluding_suspend(),
+        cpu: crate::cpu_time_process().map(|cpu| cpu.as_nanos() as u64),
+    }
+}
+
+/// Attributes the gaps between wakeups of the detector.
+struct Detector {
+    last: Sample,
+    /// The time elapsed and the CPU time used during the last interval without a pause,
+    /// the rate the process makes progress at while it runs.
+    rate: Option<(u64, u64)>,
+}
+
+impl Detector {
+    fn new(first: Sample) -> Detector {
+        Detector {
+            last: first,
+            rate: None,
+        }
+    }
+
+    /// Returns how long the process was paused since the previous sample.
+    fn observe(&mut self, now: Sample) -> u64 {
+        let gap = now.at.saturating_sub(self.last.at);
+        let progress = match (self.last.cpu, now.cpu) {
+            (Some(last), Some(cpu)) => Some(cpu.saturating_sub(last)),
+            _ => None,
+        };
+        self.last = now;
+
+        let paused = paused(gap, progress, self.rate);
+        if paused == 0 {
+            self.rate = progress.map(|progress| (gap, progress));
+        }
+        paused
+    }
+}
+
+/// The part of a `gap` between two wakeups of the detector that the process was paused,
+/// given the CPU time it used meanwhile and its usual rate of progress.
+///
+/// Without CPU times, every late wakeup counts as a pause.
+fn paused(gap: u64, progress: Option<u64>, rate: Option<(u64, u64)>) -> u64 {
+    // How long the process ran during the gap, at its usual rate.
+    let running = match (progress, rate) {
+        (Some(0), _) | (None, _) | (Some(_), None) => 0,
+        (Some(_), Some((_, 0))) => gap,
+        (Some(progress), Some((elapsed, cpu))) => {
+            let running = u128::from(progress) * u128::from(elapsed) / u128::from(cpu);
+            running.min(u128::from(gap)) as u64
+        }
+    };
+
+    let paused = gap.saturating_sub(running.max(INTERVAL.as_nanos() as u64));
+    if paused > TOLERANCE.as_nanos() as u64 {
+        paused
+    } else {
+        0
+    }
+}
+
+/// Returns how long the process was stopped or frozen, e.g. by `SIGSTOP`, a debugger or
+/// the cgroup freezer, since the first call.
+///
+/// Unlike system suspend, these pauses advance both [`now`](crate::now) and
+/// [`now_excluding_suspend`](crate::now_excluding_suspend): the rest of the system keeps
+/// running, only the process doesn't. Subtracting the paused time from a latency measurement
+/// shows how much of it the process actually experienced.
+///
+/// The first call starts a background thread that wakes up every 50 ms, measuring on the
+/// suspend-excluding clock, so system suspend is never counted.
+/// When it wakes up late, the process's CPU time shows whether it kept running meanwhile,
+/// e.g. on its other threads or within a cgroup CPU quota, which isn't counted as a pause.
+/// Only the part of the delay in which the process made no progress is.
+/// Pauses are only detected while that thread runs, and with a granularity of its interval.
+///
+/// A process that gets no CPU time at all for more than 100 ms, although it could run, looks
+/// paused as well. So does every late wakeup where the CPU time of the process isn't available,
+/// see [`cpu_time_process`](crate::cpu_time_process).
+pub fn process_paused_time() -> Duration {
+    Lazy::force(&DETECTOR);
+    Duration::from_nanos(PAUSED.load(Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MS: u64 = 1_000_000;
+
+    #[test]
+    fn attributes_late_wakeups() {
+        assert_eq!(0, paused(50 * MS, None, None));
+        assert_eq!(0, paused(99 * MS, None, None));
+        assert_eq!(950 * MS, paused(1_000 * MS, None, None));
+    }
+
+    #[test]
+    fn counts_only_time_without_progress() {
+        // Stopped for a second, after running on one core for 50 ms.
+        let rate = Some((50 * MS, 50 * MS));
+        assert_eq!(1_000 * MS, paused(1_050 * MS, Some(50 * MS), rate));
+
+        // Throttled to 20% of a core: the CPU time accounts for the whole gap.
+        let rate = Some((50 * MS, 10 * MS));
+        assert_eq!(0, paused(400 * MS, Some(80 * MS), rate));
+
+        // An idle process stopped for a second.
+        let rate = Some((50 * MS, MS / 10));
+        assert_eq!(950 * MS, paused(1_000 * MS, Some(0), rate));
+    }
+
+    #[test]
+    fn learns_the_rate() {
+        let mut detector = Detector::new(Sample {
+            at: 0,
+            cpu: Some(0),
+        });
+        assert_eq!(
+            0,
+            detector.observe(Sample {
+                at: 50 * MS,
+                cpu: Some(10 * MS),
+            })
+        );
+        // Busy on other threads at the same rate while the detector was starved.
+        assert_eq!(
+            0,
+            detector.observe(Sample {
+                at: 450 * MS,
+                cpu: Some(90 * MS),
+            })
+        );
+        // No progress for half a second.
+        assert_eq!(
+            450 * MS,
+            detector.observe(Sample {
+                at: 950 * MS,
+                cpu: Some(90 * MS),
+            })
+        );
+    }
+
+    #[test]
+    fn starts_without_pauses() {
+        assert_eq!(Duration::from_secs(0), process_paused_time());
+    }
+}
@@ -0,0 +1,111 @@
+//! Relating persisted timestamps to the current boot, even across reboots.
+//!
+//! Timestamps are only comparable within a single boot.
+//! A [`PersistedTimestamp`] additionally records when that boot happened,
+//! so [`duration_since_persisted`] can still estimate the elapsed time after a reboot,
+//! based on the wall clock.
+
+use std::time::Duration;
+
+use crate::boot;
+
+/// A timestamp together with the time of the boot it was taken in.
+///
+/// Store both fields to compare the timestamp with later ones using
+/// [`duration_since_persisted`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PersistedTimestamp {
+    /// The timestamp, as returned by [`now`](crate::now).
+    pub timestamp: u64,
+    /// When the clock started, usually at boot, in nanoseconds since the Unix epoch.
+    pub boot_time: u64,
+}
+
+impl PersistedTimestamp {
+    /// Returns a timestamp corresponding to "now".
+    pub fn now() -> PersistedTimestamp {
+        PersistedTimestamp {
+            timestamp: crate::now(),
+            boot_time: boot::boot_time(),
+        }
+    }
+}
+
+/// How much a duration from [`duration_since_persisted`] can be trusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    /// The timestamp was taken during the current boot.
+    /// The duration was measured on the clock and includes time spent in suspend.
+    Exact,
+    /// The timestamp was taken during an earlier boot.
+    /// The duration was estimated from the wall clock and is off by however much the wall clock
+    /// was adjusted in between.
+    Estimated,
+}
+
+/// The result of [`duration_since_persisted`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PersistedElapsed {
+    /// The time elapsed since the persisted timestamp.
+    pub duration: Duration,
+    /// Whether the duration is exact or an estimate.
+    pub confidence: Confidence,
+}
+
+/// Returns the time elapsed since a persisted timestamp, even if the system rebooted since.
+///
+/// A timestamp from the current boot is compared directly.
+/// For one from an earlier boot, the elapsed time is estimated by bridging through the recorded
+/// wall-clock boot times.
+/// Durations never go negative: a timestamp in the future yields a zero duration.
+pub fn duration_since_persisted(persisted: &PersistedTimestamp) -> PersistedElapsed {
+    let now = crate::now();
+    let boot_time = boot::boot_time();
+
+    let same_boot =
+        boot::same_boot_time(boot_time, persisted.boot_time) && persisted.timestamp <= now;
+
+    if same_boot {
+        PersistedElapsed {
+            duration: Duration::from_nanos(now - persisted.timestamp),
+            confidence: Confidence::Exact,
+        }
+    } else {
+        let then = persisted.boot_time.saturating_add(persisted.timestamp);
+        let now = boot_time.saturating_add(now);
+        PersistedElapsed {
+            duration: Duration::from_nanos(now.saturating_sub(then)),
+            confidence: Confidence::Estimated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_boot_is_exact() {
+        let persisted = PersistedTimestamp::now();
+        let elapsed = duration_since_persisted(&persisted);
+
+        assert_eq!(Confidence::Exact, elapsed.confidence);
+        assert!(elapsed.duration < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn earlier_boot_is_estimated() {
+        // Taken 10 minutes into a boot that happened two hours before the current one.
+        let current = PersistedTimestamp::now();
+        let persisted = PersistedTimestamp {
+            timestamp: 600 * 1_000_000_000,
+            boot_time: current.boot_time - 7200 * 1_000_000_000,
+        };
+        let elapsed = duration_since_persisted(&persisted);
+
+        assert_eq!(Confidence::Estimated, elapsed.confidence);
+        let expected = Duration::from_secs(6600) + Duration::from_nanos(current.timestamp);
+        assert!(elapsed.duration >= expected);
+        assert!(elapsed.duration - expected < Duration::from_secs(1));
+    }
+}
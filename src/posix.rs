@@ -0,0 +1,42 @@
+//! `clock_gettime` for the backends of Unix-like systems other than Linux and Apple's.
+
+use std::time::Duration;
+
+use crate::error::Error;
+
+const NS_PER_S: u64 = 1_000_000_000;
+
+fn timespec_to_ns(ts: libc::timespec) -> u64 {
+    (ts.tv_sec as u64) * NS_PER_S + (ts.tv_nsec as u64)
+}
+
+/// Reads `clock` in nanoseconds.
+pub(crate) fn clock_gettime(clock: libc::clockid_t) -> Result<u64, Error> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    let ret = unsafe { libc::clock_gettime(clock, &mut ts) };
+    if ret != 0 {
+        return match std::io::Error::last_os_error().raw_os_error() {
+            Some(libc::EINVAL) | None => Err(Error::Unavailable),
+            Some(code) => Err(Error::Os(code)),
+        };
+    }
+
+    Ok(timespec_to_ns(ts))
+}
+
+/// The resolution of `clock`, if the system reports it.
+pub(crate) fn clock_getres(clock: libc::clockid_t) -> Option<Duration> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    let ret = unsafe { libc::clock_getres(clock, &mut ts) };
+    if ret != 0 {
+        return None;
+    }
+
+    Some(Duration::from_nanos(timespec_to_ns(ts)))
+}
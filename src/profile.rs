@@ -0,0 +1,133 @@
+//! A scoped profiler measuring nested regions of code, including time spent in suspend.
+//!
+//! Every [`scope`] records the time until its guard is dropped under the stack of scopes
+//! that are open on the current thread. Recordings from all threads are combined and can be
+//! exported with [`write_folded`] as folded stacks, the input format of
+//! [inferno](https://github.com/jonhoo/inferno) and
+//! [flamegraph.pl](https://github.com/brendangregg/FlameGraph):
+//!
+//! ```
+//! use zeitstempel::profile;
+//!
+//! {
+//!     let _request = profile::scope("request");
+//!     let _parse = profile::scope("parse");
+//! }
+//!
+//! let mut folded = Vec::new();
+//! profile::write_folded(&mut folded).unwrap();
+//! // request 1200
+//! // request;parse 3400
+//! ```
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+struct Frame {
+    name: &'static str,
+    start: u64,
+    children: u64,
+}
+
+thread_local! {
+    static STACK: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The self time of every stack recorded so far, in nanoseconds.
+static RECORDED: Mutex<BTreeMap<Vec<&'static str>, u64>> = Mutex::new(BTreeMap::new());
+
+/// Measures from [`scope`] until drop. Guards must be dropped in reverse order of creation,
+/// which they are when kept in local variables.
+#[must_use = "the scope ends when the guard is dropped"]
+pub struct Scope {
+    // Scopes live on the stack of the thread they were opened on.
+    _not_send: PhantomData<*const ()>,
+}
+
+/// Opens a scope named `name`, nested within the scopes open on the current thread.
+pub fn scope(name: &'static str) -> Scope {
+    STACK.with(|stack| {
+        stack.borrow_mut().push(Frame {
+            name,
+            start: crate::now(),
+            children: 0,
+        })
+    });
+    Scope {
+        _not_send: PhantomData,
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let now = crate::now();
+        STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let path: Vec<&'static str> = stack.iter().map(|frame| frame.name).collect();
+            let frame = match stack.pop() {
+                Some(frame) => frame,
+                None => return,
+            };
+
+            let total = now.saturating_sub(frame.start);
+            if let Some(parent) = stack.last_mut() {
+                parent.children = parent.children.saturating_add(total);
+            }
+
+            let mut recorded = RECORDED.lock().unwrap_or_else(|e| e.into_inner());
+            let time = recorded.entry(path).or_insert(0);
+            *time = time.saturating_add(total.saturating_sub(frame.children));
+        });
+    }
+}
+
+/// Writes the recorded scopes as folded stacks: one line per stack, the scope names joined
+/// by `;`, followed by the time spent in the innermost scope itself, in nanoseconds.
+///
+/// Stacks are written in lexicographic order.
+pub fn write_folded<W: Write>(mut writer: W) -> io::Result<()> {
+    let recorded = RECORDED.lock().unwrap_or_else(|e| e.into_inner());
+    for (path, time) in recorded.iter() {
+        writeln!(writer, "{} {}", path.join(";"), time)?;
+    }
+    Ok(())
+}
+
+/// Discards all recordings.
+pub fn reset() {
+    RECORDED.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn folds_nested_scopes() {
+        {
+            let _outer = scope("test_outer");
+            thread::sleep(Duration::from_millis(2));
+            let _inner = scope("test_inner");
+            thread::sleep(Duration::from_millis(2));
+        }
+
+        let mut folded = Vec::new();
+        write_folded(&mut folded).unwrap();
+        let folded = String::from_utf8(folded).unwrap();
+        let time = |stack: &str| -> u64 {
+            let line = folded
+                .lines()
+                .find(|line| line.rsplit_once(' ').unwrap().0 == stack)
+                .unwrap();
+            line.rsplit_once(' ').unwrap().1.parse().unwrap()
+        };
+
+        assert!(time("test_outer") >= 2_000_000);
+        assert!(time("test_outer;test_inner") >= 2_000_000);
+    }
+}
@@ -0,0 +1,134 @@
+//! A Python extension module, with the `python` feature.
+//!
+//! Build it with [maturin], enabling pyo3's `extension-module` feature:
+//!
+//! ```text
+//! maturin build --release --features python,pyo3/extension-module
+//! ```
+//!
+//! Timestamps created in Python are the same integers as those of [`now`](crate::now), so
+//! they can be compared with timestamps from Rust, or deserialized ones, of the same boot:
+//!
+//! ```text
+//! >>> import zeitstempel
+//! >>> start = zeitstempel.now()
+//! >>> zeitstempel.elapsed(start)
+//! datetime.timedelta(microseconds=3)
+//! >>> zeitstempel.clock_info().includes_suspend
+//! True
+//! ```
+//!
+//! [maturin]: https://www.maturin.rs/
+
+use std::time::Duration;
+
+use pyo3::prelude::*;
+
+use crate::Timestamp;
+
+/// Describes the clock behind `now()`, see [`ClockInfo`](crate::ClockInfo).
+#[pyclass(name = "ClockInfo", module = "zeitstempel", frozen, get_all)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PyClockInfo {
+    /// The name of the operating system's clock or function.
+    name: String,
+    /// The resolution the clock reports for itself, if it does.
+    resolution: Option<Duration>,
+    /// Whether time the system spends in sleep or hibernation is counted on this platform.
+    includes_suspend: bool,
+}
+
+#[pymethods]
+impl PyClockInfo {
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let resolution = self.resolution.into_pyobject(py)?.repr()?;
+        Ok(format!(
+            "ClockInfo(name='{}', resolution={}, includes_suspend={})",
+            self.name,
+            resolution,
+            if self.includes_suspend {
+                "True"
+            } else {
+                "False"
+            }
+        ))
+    }
+}
+
+/// Returns a timestamp corresponding to "now", in nanoseconds.
+#[pyfunction]
+fn now() -> u64 {
+    crate::now()
+}
+
+/// Returns a timestamp corresponding to "now", without the time spent in suspend.
+#[pyfunction]
+fn now_excluding_suspend() -> u64 {
+    crate::now_excluding_suspend()
+}
+
+/// Describes the clock behind `now()`.
+#[pyfunction]
+fn clock_info() -> PyClockInfo {
+    let info = crate::clock_info();
+    PyClockInfo {
+        name: info.name.to_string(),
+        resolution: info.resolution,
+        includes_suspend: info.includes_suspend,
+    }
+}
+
+/// Returns the time elapsed since `start`, a timestamp from `now()`.
+#[pyfunction]
+fn elapsed(start: u64) -> Duration {
+    duration_since(crate::now(), start)
+}
+
+/// Returns the time elapsed from `earlier` to `later`, or zero if `earlier` is later.
+#[pyfunction]
+fn duration_since(later: u64, earlier: u64) -> Duration {
+    Timestamp::from(later) - Timestamp::from(earlier)
+}
+
+/// Returns the timestamp `duration` after `ts`, saturating at the largest timestamp.
+#[pyfunction]
+fn add(ts: u64, duration: Duration) -> u64 {
+    Timestamp::from(ts).saturating_add(duration).into()
+}
+
+/// Returns the timestamp `duration` before `ts`, saturating at zero.
+#[pyfunction]
+fn sub(ts: u64, duration: Duration) -> u64 {
+    Timestamp::from(ts).saturating_sub(duration).into()
+}
+
+/// The `zeitstempel` Python module.
+#[pymodule]
+fn zeitstempel(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyClockInfo>()?;
+    m.add_function(wrap_pyfunction!(now, m)?)?;
+    m.add_function(wrap_pyfunction!(now_excluding_suspend, m)?)?;
+    m.add_function(wrap_pyfunction!(clock_info, m)?)?;
+    m.add_function(wrap_pyfunction!(elapsed, m)?)?;
+    m.add_function(wrap_pyfunction!(duration_since, m)?)?;
+    m.add_function(wrap_pyfunction!(add, m)?)?;
+    m.add_function(wrap_pyfunction!(sub, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let ts = 5_000_000_000;
+        let later = add(ts, Duration::from_secs(2));
+        assert_eq!(7_000_000_000, later);
+        assert_eq!(ts, sub(later, Duration::from_secs(2)));
+        assert_eq!(Duration::from_secs(2), duration_since(later, ts));
+        assert_eq!(Duration::ZERO, duration_since(ts, later));
+        assert_eq!(0, sub(ts, Duration::MAX));
+        assert_eq!(u64::MAX, add(ts, Duration::MAX));
+    }
+}
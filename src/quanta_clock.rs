@@ -0,0 +1,133 @@
+//! Relating [quanta] readings to timestamps, available with the `quanta` feature.
+//!
+//! quanta reads the TSC, which is fast but stops while the system is suspended.
+//! A `quanta::Clock` can't be given another source, so instead a [`QuantaAnchor`] pairs a
+//! reading of both clocks, to convert between the two timelines.
+//!
+//! ```
+//! use zeitstempel::quanta_clock::QuantaAnchor;
+//!
+//! let clock = quanta::Clock::new();
+//! let anchor = QuantaAnchor::with_clock(clock.clone());
+//!
+//! let start = clock.now();
+//! let ts = anchor.to_timestamp(start);
+//! assert!(ts <= zeitstempel::now());
+//! ```
+//!
+//! [quanta]: https://docs.rs/quanta
+
+use std::convert::TryInto;
+use std::time::Duration;
+
+use quanta::{Clock, Instant};
+
+/// A paired reading of [`now`](crate::now) and a [`quanta::Clock`].
+///
+/// The conversion assumes both clocks advanced at the same pace since the anchor was created.
+/// Once the system was suspended, quanta falls behind by the time spent in suspend, see
+/// [`suspended`](QuantaAnchor::suspended): create a new anchor to pick that up.
+#[derive(Debug, Clone)]
+pub struct QuantaAnchor {
+    clock: Clock,
+    ts: u64,
+    instant: Instant,
+    uncertainty: Duration,
+}
+
+impl QuantaAnchor {
+    /// Reads both clocks "now", with a new `quanta::Clock`.
+    pub fn new() -> QuantaAnchor {
+        QuantaAnchor::with_clock(Clock::new())
+    }
+
+    /// Reads both clocks "now".
+    ///
+    /// `clock` is read between two timestamps, and paired with their midpoint.
+    pub fn with_clock(clock: Clock) -> QuantaAnchor {
+        let before = crate::now();
+        let instant = clock.now();
+        let after = crate::now();
+
+        let gap = after.saturating_sub(before);
+        QuantaAnchor {
+            clock,
+            ts: before + gap / 2,
+            instant,
+            uncertainty: Duration::from_nanos(gap.div_ceil(2)),
+        }
+    }
+
+    /// Returns how far apart the paired readings can be at most.
+    pub fn uncertainty(&self) -> Duration {
+        self.uncertainty
+    }
+
+    /// Converts a quanta reading to the timestamp [`now`](crate::now) returned at that time.
+    ///
+    /// Saturates at zero for readings before the clock started, and at `u64::MAX`.
+    pub fn to_timestamp(&self, instant: Instant) -> u64 {
+        let nanos = |duration: Duration| duration.as_nanos().try_into().unwrap_or(u64::MAX);
+        if instant >= self.instant {
+            self.ts.saturating_add(nanos(instant - self.instant))
+        } else {
+            self.ts.saturating_sub(nanos(self.instant - instant))
+        }
+    }
+
+    /// Converts a timestamp from [`now`](crate::now) to the quanta reading at that time.
+    ///
+    /// Readings quanta can't represent saturate at the anchor's reading.
+    pub fn to_instant(&self, ts: u64) -> Instant {
+        let converted = if ts >= self.ts {
+            self.instant.checked_add(Duration::from_nanos(ts - self.ts))
+        } else {
+            self.instant.checked_sub(Duration::from_nanos(self.ts - ts))
+        };
+        converted.unwrap_or(self.instant)
+    }
+
+    /// Returns how far quanta fell behind since the anchor was created,
+    /// usually the time the system spent suspended since.
+    pub fn suspended(&self) -> Duration {
+        let ts = Duration::from_nanos(crate::now().saturating_sub(self.ts));
+        let quanta = self.clock.now().saturating_duration_since(self.instant);
+        ts.saturating_sub(quanta)
+    }
+}
+
+impl Default for QuantaAnchor {
+    fn default() -> QuantaAnchor {
+        QuantaAnchor::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let (clock, mock) = Clock::mock();
+        mock.increment(Duration::from_secs(10));
+        let anchor = QuantaAnchor::with_clock(clock.clone());
+
+        let instant = clock.now();
+        let later = instant + Duration::from_millis(5);
+        let earlier = instant - Duration::from_millis(5);
+
+        let ts = anchor.to_timestamp(later);
+        assert_eq!(later, anchor.to_instant(ts));
+        assert_eq!(ts - 10_000_000, anchor.to_timestamp(earlier));
+        assert_eq!(earlier, anchor.to_instant(ts - 10_000_000));
+    }
+
+    #[test]
+    fn mocked_quanta_falls_behind() {
+        let (clock, _mock) = Clock::mock();
+        let anchor = QuantaAnchor::with_clock(clock);
+
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(anchor.suspended() >= Duration::from_millis(2));
+    }
+}
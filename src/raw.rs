@@ -0,0 +1,264 @@
+//! Unconverted readings of the platform clocks.
+//!
+//! With the `raw` feature enabled this module also exposes the backing functions of each
+//! supported operating system, so specific OS clocks can be used directly.
+
+use core::convert::TryInto;
+use core::time::Duration;
+
+/// Clocks on Linux and Android, read with `clock_gettime`.
+#[cfg(all(
+    feature = "raw",
+    feature = "std",
+    any(target_os = "linux", target_os = "android")
+))]
+pub mod linux {
+    pub use crate::linux::{
+        now_excluding_suspend, now_including_suspend, raw_now_including_suspend, tick_unit,
+    };
+}
+
+/// Clocks on macOS and iOS, read with `clock_gettime_nsec_np` and `mach_continuous_time`.
+#[cfg(all(
+    feature = "raw",
+    feature = "std",
+    any(target_os = "macos", target_os = "ios")
+))]
+pub mod mac {
+    pub use crate::mac::{
+        now_excluding_suspend, now_including_suspend, raw_now_including_suspend, tick_unit,
+    };
+}
+
+/// Clocks on Windows, read with `QueryInterruptTime` and `QueryUnbiasedInterruptTime`.
+#[cfg(all(
+    feature = "raw",
+    feature = "std",
+    windows,
+    not(feature = "forbid-unsafe")
+))]
+pub mod windows {
+    pub use crate::win::{
+        now_excluding_suspend, now_including_suspend, raw_now_including_suspend, tick_unit,
+    };
+}
+
+/// The unit of a [`RawTimestamp`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TickUnit {
+    /// One tick is one nanosecond.
+    Nanoseconds,
+    /// One tick is 100 nanoseconds, the system time unit used on Windows.
+    HundredNanoseconds,
+    /// One tick is `numer / denom` nanoseconds, e.g. Mach absolute time units on macOS.
+    Timebase {
+        /// The numerator of the tick length in nanoseconds.
+        numer: u32,
+        /// The denominator of the tick length in nanoseconds.
+        denom: u32,
+    },
+}
+
+impl TickUnit {
+    /// Converts a number of ticks in this unit to nanoseconds.
+    ///
+    /// Saturates at `u64::MAX`.
+    pub fn to_nanos(self, ticks: u64) -> u64 {
+        let (numer, denom) = match self {
+            TickUnit::Nanoseconds => return ticks,
+            TickUnit::HundredNanoseconds => return ticks.saturating_mul(100),
+            TickUnit::Timebase { numer, denom } => (numer, denom.max(1)),
+        };
+
+        (u128::from(ticks) * u128::from(numer) / u128::from(denom))
+            .try_into()
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Returns how many ticks of this unit make up a second.
+    ///
+    /// This is exact for the units of all supported clocks, such as the 24 MHz of Apple Silicon's
+    /// Mach ticks. A tick that doesn't divide a second evenly is rounded down, but at least 1.
+    pub fn ticks_per_second(self) -> u64 {
+        let (numer, denom) = match self {
+            TickUnit::Nanoseconds => return 1_000_000_000,
+            TickUnit::HundredNanoseconds => return 10_000_000,
+            TickUnit::Timebase { numer, denom } => (numer.max(1), denom),
+        };
+
+        (1_000_000_000 * u64::from(denom) / u64::from(numer)).max(1)
+    }
+
+    /// Returns the length of one tick of this unit, rounded down to whole nanoseconds.
+    ///
+    /// Use [`ticks_per_second`](TickUnit::ticks_per_second) for exact conversions of ticks
+    /// shorter than a nanosecond or of a fractional number of nanoseconds.
+    pub fn tick_duration(self) -> Duration {
+        Duration::from_nanos(self.to_nanos(1))
+    }
+
+    /// Returns how many whole ticks of this unit fit into `nanos` nanoseconds.
+    #[cfg(feature = "testing")]
+    pub(crate) fn ticks_in(self, nanos: u64) -> u64 {
+        let (numer, denom) = match self {
+            TickUnit::Nanoseconds => return nanos,
+            TickUnit::HundredNanoseconds => return nanos / 100,
+            TickUnit::Timebase { numer, denom } => (numer.max(1), denom),
+        };
+
+        (u128::from(nanos) * u128::from(denom) / u128::from(numer))
+            .try_into()
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Converts a number of ticks in this unit to whole microseconds.
+    pub fn to_micros(self, ticks: u64) -> u64 {
+        self.to_whole(ticks, 1_000)
+    }
+
+    /// Converts a number of ticks in this unit to whole milliseconds.
+    pub fn to_millis(self, ticks: u64) -> u64 {
+        self.to_whole(ticks, 1_000_000)
+    }
+
+    /// Converts to units of `unit_ns` nanoseconds, a multiple of 100,
+    /// without going through nanoseconds, so ticks coarser than a nanosecond never saturate.
+    fn to_whole(self, ticks: u64, unit_ns: u64) -> u64 {
+        let (numer, denom) = match self {
+            TickUnit::Nanoseconds => return ticks / unit_ns,
+            TickUnit::HundredNanoseconds => return ticks / (unit_ns / 100),
+            TickUnit::Timebase { numer, denom } => (numer, denom.max(1)),
+        };
+
+        (u128::from(ticks) * u128::from(numer) / (u128::from(denom) * u128::from(unit_ns)))
+            .try_into()
+            .unwrap_or(u64::MAX)
+    }
+}
+
+/// A timestamp in the platform's native unit, as read from the clock.
+///
+/// Converting many of these in bulk later avoids the conversion cost at capture time.
+/// [`to_nanos`](RawTimestamp::to_nanos) yields the same value [`now`](crate::now) would have
+/// returned at the time of capture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawTimestamp {
+    /// The clock reading, in ticks.
+    pub value: u64,
+    /// The length of a tick.
+    pub unit: TickUnit,
+}
+
+impl RawTimestamp {
+    /// Converts the reading to nanoseconds.
+    pub fn to_nanos(self) -> u64 {
+        self.unit.to_nanos(self.value)
+    }
+
+    /// Converts the reading to whole microseconds.
+    pub fn to_micros(self) -> u64 {
+        self.unit.to_micros(self.value)
+    }
+
+    /// Converts the reading to whole milliseconds.
+    pub fn to_millis(self) -> u64 {
+        self.unit.to_millis(self.value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn conversion() {
+        assert_eq!(7, TickUnit::Nanoseconds.to_nanos(7));
+        assert_eq!(700, TickUnit::HundredNanoseconds.to_nanos(7));
+        assert_eq!(
+            291,
+            TickUnit::Timebase {
+                numer: 125,
+                denom: 3
+            }
+            .to_nanos(7)
+        );
+        assert_eq!(u64::MAX, TickUnit::HundredNanoseconds.to_nanos(u64::MAX));
+    }
+
+    #[test]
+    fn tick_length() {
+        let mach = TickUnit::Timebase {
+            numer: 125,
+            denom: 3,
+        };
+        assert_eq!(24_000_000, mach.ticks_per_second());
+        assert_eq!(Duration::from_nanos(41), mach.tick_duration());
+        assert_eq!(10_000_000, TickUnit::HundredNanoseconds.ticks_per_second());
+        assert_eq!(
+            Duration::from_nanos(100),
+            TickUnit::HundredNanoseconds.tick_duration()
+        );
+        assert_eq!(1_000_000_000, TickUnit::Nanoseconds.ticks_per_second());
+    }
+
+    #[test]
+    fn whole_units() {
+        assert_eq!(1, TickUnit::Nanoseconds.to_micros(1_999));
+        assert_eq!(12, TickUnit::HundredNanoseconds.to_millis(123_456));
+        let ms = TickUnit::Timebase {
+            numer: 1_000_000,
+            denom: 1,
+        };
+        assert_eq!(u64::MAX, ms.to_millis(u64::MAX));
+        assert_eq!(
+            u64::MAX / 10_000,
+            TickUnit::HundredNanoseconds.to_millis(u64::MAX)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn ticks_in() {
+        let mach = TickUnit::Timebase {
+            numer: 125,
+            denom: 3,
+        };
+        assert_eq!(24_000_000, mach.ticks_in(1_000_000_000));
+        assert_eq!(7, TickUnit::HundredNanoseconds.ticks_in(799));
+        assert_eq!(7, TickUnit::Nanoseconds.ticks_in(7));
+    }
+}
+
+/// Proofs that the conversions never panic, checked with `cargo kani`.
+#[cfg(kani)]
+mod verification {
+    use super::*;
+
+    #[kani::proof]
+    fn to_nanos_never_panics() {
+        let unit = match kani::any::<u8>() % 3 {
+            0 => TickUnit::Nanoseconds,
+            1 => TickUnit::HundredNanoseconds,
+            _ => TickUnit::Timebase {
+                numer: kani::any(),
+                denom: kani::any(),
+            },
+        };
+        let ticks: u64 = kani::any();
+
+        let nanos = unit.to_nanos(ticks);
+        if unit == TickUnit::Nanoseconds {
+            assert_eq!(ticks, nanos);
+        }
+    }
+
+    #[kani::proof]
+    fn hundred_nanoseconds_saturates() {
+        let ticks: u64 = kani::any();
+        let nanos = TickUnit::HundredNanoseconds.to_nanos(ticks);
+        assert!(nanos >= ticks || ticks == 0);
+        if ticks > u64::MAX / 100 {
+            assert_eq!(u64::MAX, nanos);
+        }
+    }
+}
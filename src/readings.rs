@@ -0,0 +1,81 @@
+use core::time::Duration;
+
+/// Readings of both clocks, taken back-to-back by [`now_both`](crate::now_both).
+///
+/// ```
+/// let readings = zeitstempel::now_both();
+/// println!("suspended for {:?} since boot", readings.suspended());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Readings {
+    /// The reading of [`now`](crate::now).
+    pub including_suspend: u64,
+    /// The reading of [`now_excluding_suspend`](crate::now_excluding_suspend).
+    pub excluding_suspend: u64,
+}
+
+impl Readings {
+    /// Both readings from a single clock, for platforms that can't tell suspend time apart.
+    pub(crate) fn same(now: u64) -> Readings {
+        Readings {
+            including_suspend: now,
+            excluding_suspend: now,
+        }
+    }
+
+    /// Returns how far the clocks are apart, the time spent in suspend since boot on most
+    /// systems.
+    pub fn suspended(&self) -> Duration {
+        Duration::from_nanos(
+            self.including_suspend
+                .saturating_sub(self.excluding_suspend),
+        )
+    }
+}
+
+/// Reads `excluding` between two readings of `including`, keeping the tightest of a few attempts.
+///
+/// The excluding reading is paired with the midpoint of the including ones, so the pair is off
+/// by at most half the time one read takes.
+// Backends with a single clock return it twice instead.
+#[allow(dead_code)]
+pub(crate) fn bracketed(including: fn() -> u64, excluding: fn() -> u64) -> Readings {
+    (0..3)
+        .map(|_| {
+            let before = including();
+            let excluding = excluding();
+            let after = including().max(before);
+            (after - before, before + (after - before) / 2, excluding)
+        })
+        .min_by_key(|&(gap, _, _)| gap)
+        .map(|(_, including_suspend, excluding_suspend)| Readings {
+            including_suspend,
+            excluding_suspend,
+        })
+        .unwrap_or_else(|| Readings::same(0))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pairs_midpoint() {
+        fn including() -> u64 {
+            use core::sync::atomic::{AtomicU64, Ordering};
+            static NOW: AtomicU64 = AtomicU64::new(1_000);
+            NOW.fetch_add(10, Ordering::Relaxed)
+        }
+        fn excluding() -> u64 {
+            400
+        }
+
+        let readings = bracketed(including, excluding);
+        assert_eq!(400, readings.excluding_suspend);
+        assert_eq!(5, readings.including_suspend % 10);
+        assert_eq!(
+            Duration::from_nanos(readings.including_suspend - 400),
+            readings.suspended()
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Retrying fallible operations until a deadline on the suspend-including clock.
+//!
+//! Retry loops built on [`std::time::Instant`] keep going after the system resumes,
+//! as if the suspended time never happened, and overshoot their deadline by it.
+
+use std::time::Duration;
+
+use crate::wait::{as_nanos, Waiter};
+
+/// How long to wait between attempts.
+///
+/// The delay starts at `initial` and is multiplied by `multiplier` after every failed attempt,
+/// up to `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    /// The delay after the first failed attempt.
+    pub initial: Duration,
+    /// The longest delay between two attempts.
+    pub max: Duration,
+    /// The factor the delay grows by after every failed attempt. `1` keeps it constant,
+    /// and so does `0` rather than retrying without delay.
+    pub multiplier: u32,
+}
+
+impl Default for Backoff {
+    /// Starts at 10 ms, doubling up to 10 s.
+    fn default() -> Backoff {
+        Backoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_secs(10),
+            multiplier: 2,
+        }
+    }
+}
+
+/// The outcome of [`retry_until`].
+#[derive(Debug)]
+pub struct Retried<T, E> {
+    /// The first success, or the error of the last attempt.
+    pub result: Result<T, E>,
+    /// How many times the operation was called.
+    pub attempts: u32,
+    /// The time spent retrying, including time the system spent in suspend.
+    pub elapsed: Duration,
+    /// The part of `elapsed` the system spent in suspend.
+    pub suspended: Duration,
+}
+
+/// Calls `op` until it succeeds or the `deadline` passes, waiting between attempts according
+/// to the `policy`.
+///
+/// The deadline is a timestamp as returned by [`now`](crate::now), so time the system spends
+/// in suspend counts towards it.
+/// The operation is called at least once, and never again once the deadline has passed:
+/// a delay that would end after the deadline ends the retries instead.
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// use zeitstempel::retry::{retry_until, Backoff};
+///
+/// let deadline = zeitstempel::now() + 1_000_000_000;
+/// let mut calls = 0;
+/// let retried = retry_until(deadline, Backoff::default(), || {
+///     calls += 1;
+///     if calls < 3 { Err("not yet") } else { Ok(calls) }
+/// });
+///
+/// assert_eq!(Ok(3), retried.result);
+/// assert_eq!(3, retried.attempts);
+/// ```
+pub fn retry_until<T, E, F>(deadline: u64, policy: Backoff, mut op: F) -> Retried<T, E>
+where
+    F: FnMut() -> Result<T, E>,
+{
+    let start = crate::now();
+    let start_excluding = crate::now_excluding_suspend();
+    let mut waiter = None;
+    let mut delay = policy.initial.min(policy.max);
+    let mut attempts = 0;
+
+    let result = loop {
+        attempts += 1;
+        let error = match op() {
+            Ok(value) => break Ok(value),
+            Err(error) => error,
+        };
+
+        let next = crate::now().saturating_add(as_nanos(delay));
+        if next >= deadline {
+            break Err(error);
+        }
+
+        let waiter = waiter.get_or_insert_with(Waiter::new);
+        while crate::now() < next {
+            waiter.wait_until(Some(next));
+        }
+
+        delay = delay
+            .checked_mul(policy.multiplier.max(1))
+            .unwrap_or(policy.max)
+            .min(policy.max);
+    };
+
+    let elapsed = Duration::from_nanos(crate::now().saturating_sub(start));
+    let excluding =
+        Duration::from_nanos(crate::now_excluding_suspend().saturating_sub(start_excluding));
+    Retried {
+        result,
+        attempts,
+        elapsed,
+        suspended: elapsed.saturating_sub(excluding),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gives_up_at_deadline() {
+        for &multiplier in &[1, 0] {
+            let policy = Backoff {
+                initial: Duration::from_millis(5),
+                max: Duration::from_millis(5),
+                multiplier,
+            };
+            let deadline = crate::now() + 22_000_000;
+
+            let retried = retry_until(deadline, policy, || Err::<(), _>("fails"));
+
+            assert_eq!(Err("fails"), retried.result);
+            // Attempts at 0, 5, 10, 15 and 20 ms at most, fewer if waits overshoot under load.
+            assert!(retried.attempts <= 5);
+            assert!(retried.elapsed >= Duration::from_millis(5) * (retried.attempts - 1));
+        }
+    }
+
+    #[test]
+    fn calls_at_least_once() {
+        let retried = retry_until(0, Backoff::default(), || Ok::<_, ()>(1));
+        assert_eq!(Ok(1), retried.result);
+        assert_eq!(1, retried.attempts);
+    }
+}
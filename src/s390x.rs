@@ -0,0 +1,92 @@
+//! Timestamp implementation for Linux on IBM Z, reading the TOD clock directly,
+//! with the `s390x-tod` feature.
+//!
+//! `STORE CLOCK EXTENDED` reads the time-of-day clock without a system call.
+//! The TOD clock counts from 1900, and keeps running while a guest or LPAR is suspended.
+//! Its readings are moved onto the epoch of `CLOCK_BOOTTIME` once per process,
+//! so timestamps stay comparable to those of the other Linux functions such as
+//! [`journal`](crate::journal) and [`ebpf`](crate::ebpf).
+//!
+//! Setting the TOD clock, e.g. when a guest is relocated between machines,
+//! shifts the timestamps by the difference, just as it would shift the kernel's clocks.
+
+use std::arch::asm;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::{bracketed, Readings};
+
+/// Bit 51 of the TOD clock ticks once per microsecond: one tick is 1/4096 µs.
+/// In nanoseconds that's `1000 / 4096`, reduced to `125 / 512`.
+const TOD_UNIT: TickUnit = TickUnit::Timebase {
+    numer: 125,
+    denom: 512,
+};
+
+/// The difference between `CLOCK_BOOTTIME` and the TOD clock, in TOD clock ticks.
+static OFFSET: Lazy<i128> = Lazy::new(|| {
+    let boottime = u128::from(crate::linux::now_including_suspend()) * 512 / 125;
+    boottime as i128 - stcke() as i128
+});
+
+/// Reads the TOD clock with `STCKE`, in ticks since 1900.
+///
+/// The 16 bytes stored are the epoch index, extending the clock past 2042,
+/// the 64 bits of the clock itself and 7 bytes of extra precision and programmable field,
+/// which are dropped.
+fn stcke() -> u128 {
+    let mut clock = [0u8; 16];
+    unsafe {
+        asm!("stcke 0({0})", in(reg_addr) clock.as_mut_ptr(), options(nostack));
+    }
+
+    u128::from_be_bytes(clock) >> 56
+}
+
+/// The TOD clock, in nanoseconds on the epoch of `CLOCK_BOOTTIME`.
+/// This includes the suspend time.
+pub fn now_including_suspend() -> u64 {
+    raw_now_including_suspend().to_nanos()
+}
+
+/// Storing the TOD clock can't fail.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    Ok(now_including_suspend())
+}
+
+/// The TOD clock doesn't know about suspend time, so this uses `CLOCK_MONOTONIC`.
+pub fn now_excluding_suspend() -> u64 {
+    crate::linux::now_excluding_suspend()
+}
+
+/// Reads both clocks back-to-back.
+pub fn now_both() -> Readings {
+    bracketed(now_including_suspend, now_excluding_suspend)
+}
+
+/// The TOD clock in its native unit of 1/4096 µs, on the epoch of `CLOCK_BOOTTIME`.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    let ticks = (stcke() as i128 + *OFFSET).max(0);
+    RawTimestamp {
+        value: ticks as u64,
+        unit: TOD_UNIT,
+    }
+}
+
+/// The TOD clock counts 1/4096 µs.
+pub fn tick_unit() -> TickUnit {
+    TOD_UNIT
+}
+
+/// A TOD clock tick is shorter than a nanosecond, so the resolution is a nanosecond.
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        name: "STORE CLOCK EXTENDED",
+        resolution: Some(Duration::from_nanos(1)),
+        includes_suspend: true,
+    }
+}
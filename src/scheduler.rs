@@ -0,0 +1,351 @@
+//! Run callbacks at deadlines on the suspend-including clock.
+
+use std::collections::{BTreeMap, HashMap};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::wait::{as_nanos, Waiter};
+
+enum Job {
+    Once(Box<dyn FnOnce() + Send + 'static>),
+    Every {
+        job: Box<dyn FnMut() + Send + 'static>,
+        interval: u64,
+        catch_up: CatchUp,
+    },
+}
+
+/// What a recurring job does about intervals that were missed entirely,
+/// e.g. because the system was suspended or hibernating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatchUp {
+    /// Run the job once as soon as possible, no matter how many intervals were missed.
+    RunOnce,
+    /// Skip the missed runs and wait for the next regular one.
+    Skip,
+}
+
+/// Runs closures at absolute deadlines from a dedicated background thread.
+///
+/// Deadlines are timestamps as returned by [`now`](crate::now),
+/// so time the system spends in suspend counts towards them.
+/// A job whose deadline passed during suspend runs right after the system resumes.
+///
+/// Jobs run one after the other on the scheduler thread, in deadline order.
+/// A panicking job does not take down the scheduler.
+///
+/// Recurring jobs stay on their original schedule: a job running every 30 minutes that was
+/// started at 12:00 runs at 12:30, 13:00 and so on, no matter how long a single run takes or
+/// whether the system was asleep at 12:30. See [`CatchUp`] for what happens to missed runs.
+///
+/// Dropping the scheduler stops the thread. Jobs that did not run yet are discarded.
+///
+/// # Example
+///
+/// ```
+/// # use std::{sync::mpsc, time::Duration};
+/// let scheduler = zeitstempel::Scheduler::new();
+/// let (tx, rx) = mpsc::channel();
+///
+/// scheduler.schedule_after(Duration::from_millis(2), move || tx.send(()).unwrap());
+/// rx.recv().unwrap();
+/// ```
+pub struct Scheduler {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// A handle to a scheduled job, used to cancel it.
+///
+/// Dropping the handle does *not* cancel the job.
+#[derive(Debug, Clone)]
+pub struct JobHandle {
+    id: u64,
+    shared: Weak<Shared>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    waiter: Waiter,
+}
+
+#[derive(Default)]
+struct State {
+    next_id: u64,
+    /// Pending jobs, ordered by deadline and then by insertion.
+    jobs: BTreeMap<(u64, u64), Job>,
+    /// The deadline of every pending job, by id.
+    deadlines: HashMap<u64, u64>,
+    /// The recurring job currently running and whether it was cancelled while doing so.
+    running: Option<(u64, bool)>,
+    shutdown: bool,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Scheduler {
+    /// Creates a new scheduler and starts its background thread.
+    pub fn new() -> Scheduler {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State::default()),
+            waiter: Waiter::new(),
+        });
+
+        let thread = {
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name("zeitstempel-scheduler".into())
+                .spawn(move || run(&shared))
+                .expect("failed to spawn the scheduler thread")
+        };
+
+        Scheduler {
+            shared,
+            thread: Some(thread),
+        }
+    }
+
+    /// Runs `job` once the clock reaches `deadline`.
+    ///
+    /// A deadline in the past runs the job as soon as possible.
+    pub fn schedule_at<F>(&self, deadline: u64, job: F) -> JobHandle
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.insert(deadline, Job::Once(Box::new(job)))
+    }
+
+    /// Runs `job` once `delay` has passed, including time spent in suspend.
+    pub fn schedule_after<F>(&self, delay: Duration, job: F) -> JobHandle
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.schedule_at(crate::now().saturating_add(as_nanos(delay)), job)
+    }
+
+    /// Runs `job` every `interval`, including time spent in suspend, starting one `interval`
+    /// from now.
+    ///
+    /// `catch_up` decides what happens when one or more whole intervals were missed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn schedule_every<F>(&self, interval: Duration, catch_up: CatchUp, job: F) -> JobHandle
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let interval = as_nanos(interval);
+        assert!(interval > 0, "interval must be non-zero");
+
+        let job = Job::Every {
+            job: Box::new(job),
+            interval,
+            catch_up,
+        };
+        self.insert(crate::now().saturating_add(interval), job)
+    }
+
+    fn insert(&self, deadline: u64, job: Job) -> JobHandle {
+        let id = {
+            let mut state = self.shared.lock();
+            let id = state.next_id;
+            state.next_id += 1;
+            state.jobs.insert((deadline, id), job);
+            state.deadlines.insert(id, deadline);
+            id
+        };
+        self.shared.waiter.wake();
+
+        JobHandle {
+            id,
+            shared: Arc::downgrade(&self.shared),
+        }
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Scheduler {
+        Scheduler::new()
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        self.shared.lock().shutdown = true;
+        self.shared.waiter.wake();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl JobHandle {
+    /// Cancels the job.
+    ///
+    /// Returns `true` if the job will never run again,
+    /// `false` if it already ran, was already cancelled or the scheduler is gone.
+    ///
+    /// A recurring job that is running right now finishes its current run.
+    pub fn cancel(&self) -> bool {
+        let shared = match self.shared.upgrade() {
+            Some(shared) => shared,
+            None => return false,
+        };
+
+        let mut state = shared.lock();
+        if let Some((id, cancelled)) = &mut state.running {
+            if *id == self.id {
+                return !std::mem::replace(cancelled, true);
+            }
+        }
+
+        match state.deadlines.remove(&self.id) {
+            Some(deadline) => state.jobs.remove(&(deadline, self.id)).is_some(),
+            None => false,
+        }
+    }
+}
+
+fn run(shared: &Shared) {
+    loop {
+        let next_deadline = {
+            let mut state = shared.lock();
+            if state.shutdown {
+                return;
+            }
+
+            let now = crate::now();
+            match state.jobs.keys().next().copied() {
+                Some((deadline, id)) if deadline <= now => {
+                    let job = state.jobs.remove(&(deadline, id)).unwrap();
+                    state.deadlines.remove(&id);
+
+                    match job {
+                        Job::Once(job) => {
+                            drop(state);
+                            let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                        }
+                        Job::Every {
+                            mut job,
+                            interval,
+                            catch_up,
+                        } => {
+                            state.running = Some((id, false));
+                            drop(state);
+
+                            let (runs, next) = recurrence(deadline, now, interval, catch_up);
+                            if runs {
+                                let _ = panic::catch_unwind(AssertUnwindSafe(&mut job));
+                            }
+
+                            let mut state = shared.lock();
+                            if let Some((_, false)) = state.running.take() {
+                                let job = Job::Every {
+                                    job,
+                                    interval,
+                                    catch_up,
+                                };
+                                state.jobs.insert((next, id), job);
+                                state.deadlines.insert(id, next);
+                            }
+                        }
+                    }
+                    continue;
+                }
+                Some((deadline, _)) => Some(deadline),
+                None => None,
+            }
+        };
+
+        shared.waiter.wait_until(next_deadline);
+    }
+}
+
+/// Whether a recurring job due at `deadline` runs at `now`,
+/// and its next deadline on the original schedule after `now`.
+fn recurrence(deadline: u64, now: u64, interval: u64, catch_up: CatchUp) -> (bool, u64) {
+    let late = now - deadline;
+    let runs = late < interval || catch_up == CatchUp::RunOnce;
+    let next = deadline.saturating_add((late / interval + 1).saturating_mul(interval));
+    (runs, next)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn runs_in_deadline_order() {
+        let scheduler = Scheduler::new();
+        let (tx, rx) = mpsc::channel();
+        let start = crate::now();
+
+        for &(delay, n) in &[(6_000_000, 3), (2_000_000, 1), (4_000_000, 2)] {
+            let tx = tx.clone();
+            scheduler.schedule_at(start + delay, move || tx.send(n).unwrap());
+        }
+
+        let order: Vec<i32> = rx.iter().take(3).collect();
+        assert_eq!(vec![1, 2, 3], order);
+        assert_elapsed!(start, >= Duration::from_millis(6));
+    }
+
+    #[test]
+    fn cancel() {
+        let scheduler = Scheduler::new();
+        let (tx, rx) = mpsc::channel();
+
+        let cancelled = {
+            let tx = tx.clone();
+            scheduler.schedule_after(Duration::from_secs(60), move || tx.send(1).unwrap())
+        };
+        scheduler.schedule_after(Duration::from_millis(5), move || tx.send(2).unwrap());
+
+        assert!(cancelled.cancel());
+        assert!(!cancelled.cancel());
+        assert_eq!(2, rx.recv().unwrap());
+    }
+
+    #[test]
+    fn recurring() {
+        let scheduler = Scheduler::new();
+        let (tx, rx) = mpsc::channel();
+        let start = crate::now();
+
+        let handle = scheduler.schedule_every(Duration::from_millis(2), CatchUp::Skip, move || {
+            let _ = tx.send(crate::now());
+        });
+
+        let runs: Vec<u64> = rx.iter().take(3).collect();
+        assert!(handle.cancel());
+        assert!(runs[0] - start >= 2_000_000);
+        assert!(runs[2] - start >= 6_000_000);
+    }
+
+    #[test]
+    fn on_time() {
+        for &catch_up in &[CatchUp::RunOnce, CatchUp::Skip] {
+            assert_eq!((true, 110), recurrence(100, 100, 10, catch_up));
+            assert_eq!((true, 110), recurrence(100, 109, 10, catch_up));
+        }
+    }
+
+    #[test]
+    fn catch_up() {
+        // More than two intervals late: 110 and 120 were missed.
+        assert_eq!((true, 130), recurrence(100, 125, 10, CatchUp::RunOnce));
+        assert_eq!((false, 130), recurrence(100, 125, 10, CatchUp::Skip));
+
+        // The next deadline after exactly one missed interval.
+        assert_eq!((true, 120), recurrence(100, 110, 10, CatchUp::RunOnce));
+        assert_eq!((false, 120), recurrence(100, 110, 10, CatchUp::Skip));
+    }
+}
@@ -0,0 +1,173 @@
+//! `Serialize` and `Deserialize` for the timestamp types.
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "std")]
+use crate::boot::Boot;
+#[cfg(feature = "std")]
+use crate::BootTimestamp;
+use crate::{ClockKind, Deadline, Instant, Timestamp};
+
+/// The version of the serialized [`Instant`] record.
+/// Bump it when the fields or their meaning change.
+const VERSION: u32 = 1;
+
+/// The clock name, owned where a format can't borrow it.
+/// Without the standard library there's no allocator, so it has to be borrowed.
+#[cfg(feature = "std")]
+type Name<'a> = std::borrow::Cow<'a, str>;
+#[cfg(not(feature = "std"))]
+type Name<'a> = &'a str;
+
+#[derive(Serialize, Deserialize)]
+struct Record<'a> {
+    version: u32,
+    #[serde(borrow)]
+    clock: Name<'a>,
+    nanos: u64,
+}
+
+#[cfg(feature = "std")]
+#[derive(Serialize, Deserialize)]
+struct BootRecord {
+    timestamp: Timestamp,
+    /// Hex, as not all formats support 128-bit integers.
+    boot_id: Option<String>,
+    boot_time: u64,
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Timestamp, D::Error> {
+        u64::deserialize(deserializer).map(Timestamp)
+    }
+}
+
+impl<C: ClockKind> Serialize for Instant<C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Record {
+            version: VERSION,
+            #[allow(clippy::useless_conversion)] // Only borrowed without the standard library.
+            clock: C::NAME.into(),
+            nanos: self.as_timestamp(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, C: ClockKind> Deserialize<'de> for Instant<C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Instant<C>, D::Error> {
+        let record = Record::deserialize(deserializer)?;
+        if record.version != VERSION {
+            return Err(D::Error::custom(format_args!(
+                "unsupported instant version {}, expected {}",
+                record.version, VERSION
+            )));
+        }
+        if record.clock != C::NAME {
+            return Err(D::Error::custom(format_args!(
+                "instant of the {} clock, expected {}",
+                record.clock,
+                C::NAME
+            )));
+        }
+        Ok(Instant::from_nanos(record.nanos))
+    }
+}
+
+impl<C: ClockKind> Serialize for Deadline<C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.at.serialize(serializer)
+    }
+}
+
+impl<'de, C: ClockKind> Deserialize<'de> for Deadline<C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Deadline<C>, D::Error> {
+        Instant::deserialize(deserializer).map(Deadline::at)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Serialize for BootTimestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BootRecord {
+            timestamp: self.timestamp,
+            boot_id: self.boot.id.map(|id| format!("{:032x}", id)),
+            boot_time: self.boot.time,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de> Deserialize<'de> for BootTimestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<BootTimestamp, D::Error> {
+        let record = BootRecord::deserialize(deserializer)?;
+        let id = match record.boot_id {
+            Some(id) => Some(u128::from_str_radix(&id, 16).map_err(D::Error::custom)?),
+            None => None,
+        };
+        Ok(BootTimestamp {
+            timestamp: record.timestamp,
+            boot: Boot {
+                id,
+                time: record.boot_time,
+            },
+        })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use std::time::Duration;
+
+    use crate::{BootTimestamp, Deadline, ExcludingSuspend, Instant, Timestamp};
+
+    #[test]
+    fn round_trip() {
+        let ts = Timestamp::from(42);
+        assert_eq!("42", serde_json::to_string(&ts).unwrap());
+        assert_eq!(ts, serde_json::from_str("42").unwrap());
+
+        let instant = Instant::now();
+        let json = serde_json::to_string(&instant).unwrap();
+        assert_eq!(
+            format!(
+                r#"{{"version":1,"clock":"including-suspend","nanos":{}}}"#,
+                instant.as_timestamp()
+            ),
+            json
+        );
+        assert_eq!(instant, serde_json::from_str::<Instant>(&json).unwrap());
+    }
+
+    #[test]
+    fn rejects_other_clock() {
+        let json = serde_json::to_string(&Instant::now()).unwrap();
+        assert!(serde_json::from_str::<Instant<ExcludingSuspend>>(&json).is_err());
+
+        let future = r#"{"version":2,"clock":"including-suspend","nanos":1}"#;
+        assert!(serde_json::from_str::<Instant>(future).is_err());
+    }
+
+    #[test]
+    fn deadline_round_trip() {
+        let deadline = Deadline::after(Duration::from_secs(60));
+        let json = serde_json::to_string(&deadline).unwrap();
+        assert_eq!(json, serde_json::to_string(&deadline.instant()).unwrap());
+        assert_eq!(deadline, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn boot_timestamp_round_trip() {
+        let ts = BootTimestamp::now();
+        let json = serde_json::to_string(&ts).unwrap();
+        assert_eq!(ts, serde_json::from_str(&json).unwrap());
+    }
+}
@@ -0,0 +1,103 @@
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::boot::Boot;
+
+/// Why a [`MeasurementSession`] can't report a duration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Invalidated {
+    /// The system rebooted since the session started.
+    Rebooted,
+    /// The clock ran backwards during the same boot, so the measurement can't be trusted.
+    ClockChanged,
+}
+
+impl fmt::Display for Invalidated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Invalidated::Rebooted => write!(f, "the system rebooted during the measurement"),
+            Invalidated::ClockChanged => {
+                write!(f, "the clock ran backwards during the measurement")
+            }
+        }
+    }
+}
+
+impl Error for Invalidated {}
+
+/// Measures a duration, detecting when the measurement became meaningless.
+///
+/// This is what e.g. "how long was the app open" metrics need:
+/// the duration includes time the system spent in suspend,
+/// and a reboot in between is reported instead of producing a garbage value.
+///
+/// ```
+/// use zeitstempel::MeasurementSession;
+///
+/// let session = MeasurementSession::start();
+/// // ...
+/// match session.finish() {
+///     Ok(duration) => println!("open for {:?}", duration),
+///     Err(reason) => println!("measurement discarded: {}", reason),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeasurementSession {
+    start: u64,
+    boot: Boot,
+}
+
+impl MeasurementSession {
+    /// Starts a measurement "now".
+    pub fn start() -> MeasurementSession {
+        MeasurementSession {
+            start: crate::now(),
+            boot: Boot::current(),
+        }
+    }
+
+    /// Returns the time elapsed since the measurement started.
+    ///
+    /// Fails if the system rebooted in between or the clock ran backwards.
+    /// Reboots are detected by the boot identifier where the operating system provides one,
+    /// by the estimated boot time otherwise.
+    pub fn finish(&self) -> Result<Duration, Invalidated> {
+        let now = crate::now();
+
+        if !self.boot.is_same(&Boot::current()) {
+            return Err(Invalidated::Rebooted);
+        }
+
+        match now.checked_sub(self.start) {
+            Some(elapsed) => Ok(Duration::from_nanos(elapsed)),
+            None => Err(Invalidated::ClockChanged),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn finish() {
+        let session = MeasurementSession::start();
+        assert!(session.finish().unwrap() < Duration::from_secs(1));
+
+        let rebooted = MeasurementSession {
+            boot: Boot {
+                id: session.boot.id.map(|id| !id),
+                time: session.boot.time - 3600 * 1_000_000_000,
+            },
+            ..session
+        };
+        assert_eq!(Err(Invalidated::Rebooted), rebooted.finish());
+
+        let backwards = MeasurementSession {
+            start: crate::now() + 3600 * 1_000_000_000,
+            ..session
+        };
+        assert_eq!(Err(Invalidated::ClockChanged), backwards.finish());
+    }
+}
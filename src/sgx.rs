@@ -0,0 +1,90 @@
+//! Timestamp implementation for SGX enclaves, such as `x86_64-fortanix-unknown-sgx`.
+//!
+//! Enclaves can't read a clock themselves. The host has to provide it, through an ocall or
+//! usercall which the application registers with [`set_clock_hook`].
+//!
+//! The host is untrusted. Readings that go backwards, e.g. the host replaying old values,
+//! are rejected: the clock then stays at the latest accepted reading.
+
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::error::Error;
+use crate::guard::Latest;
+use crate::info::ClockInfo;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::Readings;
+
+/// The registered hook, a `fn() -> u64` stored as a pointer, null until registered.
+static HOOK: AtomicPtr<()> = AtomicPtr::new(std::ptr::null_mut());
+
+/// The latest accepted reading.
+static LAST: Latest = Latest::new();
+
+/// Registers the function that fetches the host's suspend-including clock,
+/// e.g. `CLOCK_BOOTTIME` on a Linux host, in nanoseconds.
+///
+/// Until a hook is registered, the clock reads zero.
+/// Registering a hook replaces the previous one; it must use the same clock as the previous
+/// one, or timestamps stop advancing until the new clock catches up.
+pub fn set_clock_hook(hook: fn() -> u64) {
+    HOOK.store(hook as *mut (), Ordering::Release);
+}
+
+/// Reads the host clock through the hook, rejecting readings that go backwards.
+fn read() -> u64 {
+    let hook = HOOK.load(Ordering::Acquire);
+    if hook.is_null() {
+        return LAST.get();
+    }
+
+    let hook = unsafe { std::mem::transmute::<*mut (), fn() -> u64>(hook) };
+    let reading = hook();
+    LAST.advance(reading)
+}
+
+/// The host's suspend-including clock, as returned by the registered hook,
+/// never going backwards.
+pub fn now_including_suspend() -> u64 {
+    read()
+}
+
+/// Like [`now_including_suspend`], but reports when no hook is registered yet.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    if HOOK.load(Ordering::Acquire).is_null() {
+        return Err(Error::Unavailable);
+    }
+    Ok(read())
+}
+
+/// The host provides a single clock,
+/// so this is the same clock as [`now_including_suspend`].
+pub fn now_excluding_suspend() -> u64 {
+    read()
+}
+
+/// There's a single clock, read once for both.
+pub fn now_both() -> Readings {
+    Readings::same(now_including_suspend())
+}
+
+/// The hook returns nanoseconds already.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: read(),
+        unit: TickUnit::Nanoseconds,
+    }
+}
+
+/// [`raw_now_including_suspend`] counts nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// What the hook reads is up to the host, it should include suspend time.
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        name: "clock hook",
+        resolution: None,
+        includes_suspend: true,
+    }
+}
@@ -0,0 +1,121 @@
+//! `os_signpost` intervals, so measurements show up in Instruments.
+//!
+//! Only available on macOS and iOS with the `apple-signpost` feature.
+//! Intervals are logged under the `org.zeitstempel` subsystem with the category
+//! `PointsOfInterest` and the signpost name `measurement`; the message is the measurement's name.
+//!
+//! See [`os_signpost`].
+//!
+//! [`os_signpost`]: https://developer.apple.com/documentation/os/logging/recording_performance_data
+
+use std::ffi::CString;
+
+use libc::{c_char, c_void};
+use once_cell::sync::Lazy;
+
+type OsLog = *mut c_void;
+
+const OS_SIGNPOST_INTERVAL_BEGIN: u8 = 1;
+const OS_SIGNPOST_INTERVAL_END: u8 = 2;
+
+/// The header of an `os_log` argument buffer with one public string argument.
+///
+/// `hdr_flags` = has non-scalar arguments, `hdr_cnt` = 1,
+/// then the argument: type string with the public flag, 8 bytes in size.
+const STRING_ARG_HEADER: [u8; 4] = [0x02, 0x01, 0x22, 0x08];
+
+extern "C" {
+    static __dso_handle: u8;
+
+    fn os_log_create(subsystem: *const c_char, category: *const c_char) -> OsLog;
+    fn os_signpost_enabled(log: OsLog) -> bool;
+    fn os_signpost_id_generate(log: OsLog) -> u64;
+    fn _os_signpost_emit_with_name_impl(
+        dso: *const c_void,
+        log: OsLog,
+        kind: u8,
+        id: u64,
+        name: *const c_char,
+        format: *const c_char,
+        buf: *mut u8,
+        size: u32,
+    );
+}
+
+// `os_log` expects names and format strings in this section of the calling image.
+#[link_section = "__TEXT,__oslogstring,cstring_literals"]
+static NAME: [u8; 12] = *b"measurement\0";
+#[link_section = "__TEXT,__oslogstring,cstring_literals"]
+static FORMAT: [u8; 11] = *b"%{public}s\0";
+
+struct Log(OsLog);
+
+// Log handles are thread-safe and live for the rest of the process.
+unsafe impl Send for Log {}
+unsafe impl Sync for Log {}
+
+static LOG: Lazy<Log> = Lazy::new(|| {
+    let log = unsafe {
+        os_log_create(
+            b"org.zeitstempel\0".as_ptr() as *const c_char,
+            b"PointsOfInterest\0".as_ptr() as *const c_char,
+        )
+    };
+    Log(log)
+});
+
+/// A signpost interval that begins on creation and ends on drop.
+pub(crate) struct Interval {
+    id: u64,
+    message: Option<CString>,
+}
+
+impl Interval {
+    /// Begins an interval, unless nobody is recording signposts right now.
+    pub(crate) fn begin(message: &str) -> Interval {
+        let log = LOG.0;
+        if log.is_null() || !unsafe { os_signpost_enabled(log) } {
+            return Interval {
+                id: 0,
+                message: None,
+            };
+        }
+
+        let message = CString::new(message.replace('\0', "")).ok();
+        let id = unsafe { os_signpost_id_generate(log) };
+        emit(OS_SIGNPOST_INTERVAL_BEGIN, id, message.as_ref());
+        Interval { id, message }
+    }
+}
+
+impl Drop for Interval {
+    fn drop(&mut self) {
+        if self.message.is_some() {
+            emit(OS_SIGNPOST_INTERVAL_END, self.id, self.message.as_ref());
+        }
+    }
+}
+
+fn emit(kind: u8, id: u64, message: Option<&CString>) {
+    let message = match message {
+        Some(message) => message,
+        None => return,
+    };
+
+    let mut buf = [0u8; 12];
+    buf[..4].copy_from_slice(&STRING_ARG_HEADER);
+    buf[4..].copy_from_slice(&(message.as_ptr() as u64).to_ne_bytes());
+
+    unsafe {
+        _os_signpost_emit_with_name_impl(
+            &__dso_handle as *const u8 as *const c_void,
+            LOG.0,
+            kind,
+            id,
+            NAME.as_ptr() as *const c_char,
+            FORMAT.as_ptr() as *const c_char,
+            buf.as_mut_ptr(),
+            buf.len() as u32,
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Time lost to the hypervisor and the scheduler, on Linux only.
+//!
+//! A duration from [`now`](crate::now) counts everything: time the code ran, time the system
+//! was suspended, and time the code was ready to run but didn't get a CPU.
+//! Comparing [`Snapshot`]s taken at the start and end of an interval shows how much of it
+//! was lost to other virtual machines on the same host (steal time) and to other tasks on the
+//! same system (run delay).
+//!
+//! ```
+//! use zeitstempel::steal::Snapshot;
+//!
+//! let start = Snapshot::take();
+//! // ... the code to measure ...
+//! let lost = Snapshot::take().lost_since(&start);
+//! println!("{:?} elapsed, {:?} waiting for a CPU", lost.elapsed, lost.run_delay);
+//! ```
+
+use std::fs;
+use std::time::Duration;
+
+/// Clock ticks per second in `/proc/stat`, `USER_HZ`, which is 100 on all architectures.
+const USER_HZ: u64 = 100;
+
+/// Returns the time stolen by the hypervisor since boot, summed over all CPUs.
+///
+/// Read from the `steal` column of `/proc/stat`. Zero on bare metal.
+pub fn steal_time() -> Option<Duration> {
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    let cpu = stat.lines().find(|line| line.starts_with("cpu "))?;
+    let steal: u64 = cpu.split_whitespace().nth(8)?.parse().ok()?;
+    Some(Duration::from_millis(steal * (1_000 / USER_HZ)))
+}
+
+/// Returns the time the current thread spent runnable but waiting for a CPU.
+///
+/// Read from `/proc/thread-self/schedstat`, which needs a kernel with scheduler statistics.
+pub fn run_delay() -> Option<Duration> {
+    let schedstat = fs::read_to_string("/proc/thread-self/schedstat").ok()?;
+    let delay = schedstat.split_whitespace().nth(1)?.parse().ok()?;
+    Some(Duration::from_nanos(delay))
+}
+
+/// The clock and the lost time counters at one point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot {
+    now: u64,
+    steal: Option<Duration>,
+    run_delay: Option<Duration>,
+}
+
+/// The time lost during an interval, see [`Snapshot::lost_since`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LostTime {
+    /// The duration of the interval, including time the system spent in suspend.
+    pub elapsed: Duration,
+    /// The time stolen by the hypervisor, summed over all CPUs,
+    /// so on a machine with many CPUs it can exceed `elapsed`.
+    pub steal: Option<Duration>,
+    /// The time the thread spent waiting for a CPU.
+    /// Only meaningful if both snapshots were taken on the same thread.
+    pub run_delay: Option<Duration>,
+}
+
+impl Snapshot {
+    /// Takes a snapshot on the current thread.
+    pub fn take() -> Snapshot {
+        Snapshot {
+            now: crate::now(),
+            steal: steal_time(),
+            run_delay: run_delay(),
+        }
+    }
+
+    /// Returns the time lost between `earlier` and this snapshot.
+    ///
+    /// Counters that couldn't be read in either snapshot are `None`.
+    pub fn lost_since(&self, earlier: &Snapshot) -> LostTime {
+        let diff = |now: Option<Duration>, then: Option<Duration>| {
+            Some(now?.checked_sub(then?).unwrap_or_default())
+        };
+        LostTime {
+            elapsed: Duration::from_nanos(self.now.saturating_sub(earlier.now)),
+            steal: diff(self.steal, earlier.steal),
+            run_delay: diff(self.run_delay, earlier.run_delay),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn snapshots() {
+        let start = Snapshot::take();
+        let lost = Snapshot::take().lost_since(&start);
+        assert!(lost.elapsed < Duration::from_secs(1));
+        assert!(steal_time().is_some());
+    }
+}
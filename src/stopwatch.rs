@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use crate::instant::{ClockKind, ExcludingSuspend, IncludingSuspend, Instant};
+
+/// Measures the running time of something, with pauses and laps.
+///
+/// Like [`Instant`] it says which clock it's measured on: [`Stopwatch::start`] includes
+/// time the system spends in suspend, [`Stopwatch::start_excluding_suspend`] doesn't.
+/// Time while paused isn't counted either way.
+///
+/// With the `apple-signpost` feature, every lap is also emitted as an `os_signpost` interval
+/// named `stopwatch` on macOS and iOS, split where the stopwatch is paused.
+///
+/// ```
+/// let mut stopwatch = zeitstempel::Stopwatch::start();
+/// // ... download ...
+/// let download = stopwatch.lap();
+///
+/// stopwatch.pause();
+/// // ... wait for the user, not counted ...
+/// stopwatch.resume();
+///
+/// // ... install ...
+/// let install = stopwatch.lap();
+/// assert_eq!(&[download, install], stopwatch.laps());
+/// assert!(stopwatch.elapsed() >= download + install);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Stopwatch<C: ClockKind = IncludingSuspend> {
+    /// The time counted before the current run, in nanoseconds.
+    counted: u64,
+    /// When the current run started, `None` while paused.
+    running: Option<Instant<C>>,
+    /// The elapsed time at the end of the previous lap.
+    lap_end: u64,
+    laps: Vec<Duration>,
+    #[cfg(all(
+        feature = "apple-signpost",
+        any(target_os = "macos", target_os = "ios"),
+        not(feature = "forbid-unsafe")
+    ))]
+    interval: Interval,
+}
+
+/// The signpost interval of the current run, `None` while paused.
+#[cfg(all(
+    feature = "apple-signpost",
+    any(target_os = "macos", target_os = "ios"),
+    not(feature = "forbid-unsafe")
+))]
+struct Interval(Option<crate::signpost::Interval>);
+
+#[cfg(all(
+    feature = "apple-signpost",
+    any(target_os = "macos", target_os = "ios"),
+    not(feature = "forbid-unsafe")
+))]
+impl Interval {
+    fn begin() -> Interval {
+        Interval(Some(crate::signpost::Interval::begin("stopwatch")))
+    }
+}
+
+#[cfg(all(
+    feature = "apple-signpost",
+    any(target_os = "macos", target_os = "ios"),
+    not(feature = "forbid-unsafe")
+))]
+impl Clone for Interval {
+    /// A clone runs on its own, so it gets an interval of its own.
+    fn clone(&self) -> Interval {
+        match self.0 {
+            Some(_) => Interval::begin(),
+            None => Interval(None),
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "apple-signpost",
+    any(target_os = "macos", target_os = "ios"),
+    not(feature = "forbid-unsafe")
+))]
+impl std::fmt::Debug for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Interval").field(&self.0.is_some()).finish()
+    }
+}
+
+impl Stopwatch<IncludingSuspend> {
+    /// Starts a stopwatch on the suspend-including clock.
+    pub fn start() -> Stopwatch {
+        Stopwatch::run()
+    }
+}
+
+impl Stopwatch<ExcludingSuspend> {
+    /// Starts a stopwatch on the suspend-excluding clock.
+    pub fn start_excluding_suspend() -> Stopwatch<ExcludingSuspend> {
+        Stopwatch::run()
+    }
+}
+
+impl<C: ClockKind> Stopwatch<C> {
+    fn run() -> Stopwatch<C> {
+        Stopwatch {
+            counted: 0,
+            running: Some(Instant::read()),
+            lap_end: 0,
+            laps: Vec::new(),
+            #[cfg(all(
+                feature = "apple-signpost",
+                any(target_os = "macos", target_os = "ios"),
+                not(feature = "forbid-unsafe")
+            ))]
+            interval: Interval::begin(),
+        }
+    }
+
+    /// Ends the current signpost interval and begins the next one if still running.
+    fn split_interval(&mut self) {
+        #[cfg(all(
+            feature = "apple-signpost",
+            any(target_os = "macos", target_os = "ios"),
+            not(feature = "forbid-unsafe")
+        ))]
+        {
+            self.interval = Interval(None);
+            if self.running.is_some() {
+                self.interval = Interval::begin();
+            }
+        }
+    }
+
+    fn elapsed_nanos(&self, now: Instant<C>) -> u64 {
+        let current = self.running.map_or(0, |since| {
+            now.as_timestamp().saturating_sub(since.as_timestamp())
+        });
+        self.counted.saturating_add(current)
+    }
+
+    /// Returns the time counted so far.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed_nanos(Instant::read()))
+    }
+
+    /// Ends the current lap and starts the next one, returning the length of the ended lap.
+    ///
+    /// The first lap started with the stopwatch. Laps don't count time while paused.
+    pub fn lap(&mut self) -> Duration {
+        let elapsed = self.elapsed_nanos(Instant::read());
+        let lap = Duration::from_nanos(elapsed.saturating_sub(self.lap_end));
+        self.lap_end = elapsed;
+        self.laps.push(lap);
+        self.split_interval();
+        lap
+    }
+
+    /// Returns the length of every lap ended so far, in order.
+    pub fn laps(&self) -> &[Duration] {
+        &self.laps
+    }
+
+    /// Stops counting time until [`resume`](Stopwatch::resume). Does nothing while paused.
+    pub fn pause(&mut self) {
+        if self.running.is_some() {
+            self.counted = self.elapsed_nanos(Instant::read());
+            self.running = None;
+            self.split_interval();
+        }
+    }
+
+    /// Continues counting time after [`pause`](Stopwatch::pause). Does nothing while running.
+    pub fn resume(&mut self) {
+        if self.running.is_none() {
+            self.running = Some(Instant::read());
+            self.split_interval();
+        }
+    }
+
+    /// Returns whether the stopwatch is paused.
+    pub fn is_paused(&self) -> bool {
+        self.running.is_none()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn pauses() {
+        let mut stopwatch = Stopwatch::start_excluding_suspend();
+        thread::sleep(Duration::from_millis(2));
+        stopwatch.pause();
+        let paused = stopwatch.elapsed();
+        assert!(paused >= Duration::from_millis(2));
+        assert!(stopwatch.is_paused());
+
+        thread::sleep(Duration::from_millis(2));
+        assert_eq!(paused, stopwatch.elapsed());
+
+        stopwatch.resume();
+        assert!(!stopwatch.is_paused());
+        assert!(stopwatch.elapsed() >= paused);
+    }
+
+    #[test]
+    fn laps() {
+        let mut stopwatch = Stopwatch::start();
+        thread::sleep(Duration::from_millis(2));
+        let first = stopwatch.lap();
+        stopwatch.pause();
+        let second = stopwatch.lap();
+        thread::sleep(Duration::from_millis(2));
+
+        assert!(first >= Duration::from_millis(2));
+        assert!(second < Duration::from_millis(2));
+        assert_eq!(&[first, second], stopwatch.laps());
+        assert_eq!(first + second, stopwatch.elapsed());
+    }
+}
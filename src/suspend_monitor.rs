@@ -0,0 +1,188 @@
+//! Callbacks after the system resumed from suspend, with the `suspend-events` feature.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::generation::MIN_SUSPEND;
+use crate::wait::{as_nanos, Waiter};
+
+/// How often the monitor compares the clocks, unless set with
+/// [`SuspendMonitor::with_interval`].
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+type Callback = Box<dyn FnMut(Duration) + Send + 'static>;
+
+/// Calls registered callbacks from a background thread after the system resumed from suspend,
+/// with the time it was suspended.
+///
+/// Suspends are detected from the gap between the [`now`](crate::now) and
+/// [`now_excluding_suspend`](crate::now_excluding_suspend) clocks growing, like
+/// [`generation`](crate::generation), checked once per interval.
+/// The check waits on the suspend-including clock, so it runs right after the system resumes.
+/// Several suspends within one interval are reported once, with their total duration.
+/// Where the platform can't tell suspend time apart, the callbacks are never called.
+///
+/// This only tells after the fact: there's no callback before the system suspends.
+///
+/// Callbacks run one after the other on the monitor thread.
+/// A panicking callback does not take down the monitor.
+/// Dropping the monitor stops the thread.
+///
+/// ```
+/// let monitor = zeitstempel::SuspendMonitor::new();
+/// monitor.on_resume(|suspended| {
+///     println!("slept for {:?}, restarting measurements", suspended);
+/// });
+/// ```
+pub struct SuspendMonitor {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    waiter: Waiter,
+}
+
+#[derive(Default)]
+struct State {
+    callbacks: Vec<Callback>,
+    shutdown: bool,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl SuspendMonitor {
+    /// Creates a monitor checking the clocks every second and starts its background thread.
+    pub fn new() -> SuspendMonitor {
+        SuspendMonitor::with_interval(DEFAULT_INTERVAL)
+    }
+
+    /// Creates a monitor checking the clocks every `interval` and starts its background thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn with_interval(interval: Duration) -> SuspendMonitor {
+        assert!(
+            interval > Duration::from_secs(0),
+            "interval must be non-zero"
+        );
+
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State::default()),
+            waiter: Waiter::new(),
+        });
+
+        let thread = {
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name("zeitstempel-suspend-monitor".into())
+                .spawn(move || run(&shared, interval))
+                .expect("failed to spawn the suspend monitor thread")
+        };
+
+        SuspendMonitor {
+            shared,
+            thread: Some(thread),
+        }
+    }
+
+    /// Calls `callback` with the suspended time whenever the system resumed from suspend.
+    pub fn on_resume<F>(&self, callback: F)
+    where
+        F: FnMut(Duration) + Send + 'static,
+    {
+        self.shared.lock().callbacks.push(Box::new(callback));
+    }
+}
+
+impl Default for SuspendMonitor {
+    fn default() -> SuspendMonitor {
+        SuspendMonitor::new()
+    }
+}
+
+impl Drop for SuspendMonitor {
+    fn drop(&mut self) {
+        self.shared.lock().shutdown = true;
+        self.shared.waiter.wake();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The time suspended between two readings of [`suspended_time`](crate::suspended_time),
+/// if it's long enough to be a suspend rather than the gap between reading the clocks.
+fn resumed(last: Duration, suspended: Duration) -> Option<Duration> {
+    suspended
+        .checked_sub(last)
+        .filter(|&delta| delta >= Duration::from_nanos(MIN_SUSPEND))
+}
+
+fn run(shared: &Shared, interval: Duration) {
+    let interval = as_nanos(interval);
+    let mut last = crate::suspended_time();
+
+    loop {
+        let deadline = crate::now().saturating_add(interval);
+        while crate::now() < deadline {
+            if shared.lock().shutdown {
+                return;
+            }
+            shared.waiter.wait_until(Some(deadline));
+        }
+
+        let suspended = crate::suspended_time();
+        let delta = match resumed(last, suspended) {
+            Some(delta) => delta,
+            None => continue,
+        };
+        last = suspended;
+
+        // Callbacks may register more callbacks, so they don't run under the lock.
+        let mut callbacks = std::mem::take(&mut shared.lock().callbacks);
+        for callback in &mut callbacks {
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| callback(delta)));
+        }
+
+        let mut state = shared.lock();
+        callbacks.append(&mut state.callbacks);
+        state.callbacks = callbacks;
+        if state.shutdown {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detects_growing_gap() {
+        let second = Duration::from_secs(1);
+        assert_eq!(None, resumed(second, second + Duration::from_micros(5)));
+        assert_eq!(None, resumed(second, second - Duration::from_micros(5)));
+        assert_eq!(Some(second), resumed(second, 2 * second));
+    }
+
+    #[test]
+    fn stops_on_drop() {
+        let monitor = SuspendMonitor::with_interval(Duration::from_millis(1));
+        monitor.on_resume(|_| {});
+        thread::sleep(Duration::from_millis(5));
+
+        // The thread holds on to the shared state until it exits.
+        let shared = Arc::downgrade(&monitor.shared);
+        drop(monitor);
+        assert!(shared.upgrade().is_none());
+    }
+}
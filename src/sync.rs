@@ -0,0 +1,98 @@
+//! Timestamps shared between threads.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::Timestamp;
+
+/// A [`Timestamp`] that can be shared between threads, e.g. the last heartbeat or activity.
+///
+/// Storing uses release and loading acquire ordering: whatever a thread wrote before storing a
+/// timestamp is visible to a thread that loaded it.
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use std::time::Duration;
+/// use zeitstempel::sync::AtomicTimestamp;
+///
+/// let last_activity = Arc::new(AtomicTimestamp::now());
+///
+/// let worker = {
+///     let last_activity = last_activity.clone();
+///     thread::spawn(move || last_activity.store_now())
+/// };
+/// worker.join().unwrap();
+///
+/// if last_activity.elapsed_since_store() > Duration::from_secs(30) {
+///     println!("worker is stuck");
+/// }
+/// ```
+#[derive(Default)]
+pub struct AtomicTimestamp(AtomicU64);
+
+impl AtomicTimestamp {
+    /// Creates a cell holding `ts`.
+    pub const fn new(ts: Timestamp) -> AtomicTimestamp {
+        AtomicTimestamp(AtomicU64::new(ts.0))
+    }
+
+    /// Creates a cell holding the current time.
+    pub fn now() -> AtomicTimestamp {
+        AtomicTimestamp(AtomicU64::new(crate::now()))
+    }
+
+    /// Stores the current time.
+    ///
+    /// The stored timestamp never moves backwards: if another thread stored a later one
+    /// in the meantime, that one is kept.
+    pub fn store_now(&self) {
+        self.0.fetch_max(crate::now(), Ordering::AcqRel);
+    }
+
+    /// Stores `ts`, even if it's earlier than the stored timestamp.
+    pub fn store(&self, ts: Timestamp) {
+        self.0.store(ts.0, Ordering::Release);
+    }
+
+    /// Returns the stored timestamp.
+    pub fn load(&self) -> Timestamp {
+        Timestamp(self.0.load(Ordering::Acquire))
+    }
+
+    /// Returns the time elapsed since the stored timestamp, including time spent in suspend.
+    ///
+    /// Zero if the stored timestamp lies in the future.
+    pub fn elapsed_since_store(&self) -> Duration {
+        Duration::from_nanos(crate::now().saturating_sub(self.load().0))
+    }
+}
+
+impl fmt::Debug for AtomicTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AtomicTimestamp")
+            .field(&self.load())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn never_moves_backwards() {
+        let cell = AtomicTimestamp::new(Timestamp::MAX);
+        cell.store_now();
+        assert_eq!(Timestamp::MAX, cell.load());
+        assert_eq!(Duration::from_secs(0), cell.elapsed_since_store());
+
+        cell.store(Timestamp(crate::now()));
+        thread::sleep(Duration::from_millis(2));
+        assert!(cell.elapsed_since_store() >= Duration::from_millis(2));
+        cell.store_now();
+        assert!(cell.elapsed_since_store() < Duration::from_millis(2));
+    }
+}
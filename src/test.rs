@@ -0,0 +1,193 @@
+//! A controllable clock for deterministic tests, with the `testing` feature.
+//!
+//! Once any of these functions is called, [`now`](crate::now) and
+//! [`now_excluding_suspend`](crate::now_excluding_suspend) read a virtual clock, and with them
+//! [`Instant`](crate::Instant), [`Timestamp`](crate::Timestamp), [`Deadline`](crate::Deadline)
+//! and [`Stopwatch`](crate::Stopwatch).
+//! The virtual clock follows the real one, shifted by [`set_time`] and [`advance`],
+//! until it's stopped with [`freeze`].
+//!
+//! Anything that waits still waits on the operating system's timers, which know nothing about
+//! the virtual clock: [`advance`] doesn't wake up [`sleep`](crate::sleep),
+//! [`sleep_until`](crate::sleep_until), [`Ticker`](crate::Ticker),
+//! [`Scheduler`](crate::Scheduler), [`retry_until`](crate::retry_until) or the
+//! `SuspendMonitor`, and a frozen clock doesn't keep them waiting.
+//!
+//! The boot time behind [`BootTimestamp`](crate::BootTimestamp) and persisted timestamps is
+//! estimated from the real wall clock and the virtual one, so it's off by the virtual clock's
+//! shift, and a shift of more than a second looks like a different boot.
+//!
+//! ```
+//! use std::time::Duration;
+//! use zeitstempel::test;
+//!
+//! test::freeze();
+//! test::set_time(1_000);
+//! let deadline = zeitstempel::Timestamp::now() + Duration::from_secs(30);
+//!
+//! test::advance(Duration::from_secs(31));
+//! assert!(zeitstempel::Timestamp::now() > deadline);
+//! test::reset();
+//! ```
+//!
+//! The clock is shared by the whole process. Tests that change it must not run concurrently
+//! with other tests reading the clock, e.g. by running them with `--test-threads=1`
+//! or in their own integration test binary.
+
+use std::convert::TryInto;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The two clocks, in the order of [`State::offset`] and [`State::frozen`].
+const INCLUDING: usize = 0;
+const EXCLUDING: usize = 1;
+
+struct State {
+    /// Added to the clocks, in nanoseconds.
+    offset: [i128; 2],
+    /// The real readings when the clocks were frozen.
+    frozen: Option<[u64; 2]>,
+}
+
+impl State {
+    /// The real readings the virtual clocks are currently based on.
+    fn base(&self) -> [u64; 2] {
+        self.frozen.unwrap_or_else(real)
+    }
+
+    fn read(&self, clock: usize, real: u64) -> u64 {
+        let base = self.frozen.map_or(real, |frozen| frozen[clock]);
+        clamp(i128::from(base) + self.offset[clock])
+    }
+
+    /// Moves both clocks by `delta` nanoseconds.
+    fn shift(&mut self, delta: i128) {
+        self.offset[INCLUDING] += delta;
+        self.offset[EXCLUDING] += delta;
+    }
+}
+
+/// Whether the virtual clock is in use. Checked before locking the state,
+/// so the real clock stays cheap to read until a test changes it.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+static STATE: Mutex<State> = Mutex::new(State {
+    offset: [0; 2],
+    frozen: None,
+});
+
+fn real() -> [u64; 2] {
+    [
+        crate::sys::now_including_suspend(),
+        crate::sys::now_excluding_suspend(),
+    ]
+}
+
+fn clamp(nanos: i128) -> u64 {
+    nanos.max(0).try_into().unwrap_or(u64::MAX)
+}
+
+fn update(f: impl FnOnce(&mut State)) {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut state);
+    ACTIVE.store(true, Ordering::Release);
+}
+
+/// Sets the suspend-including clock to `nanos`.
+///
+/// The excluding clock moves by the same amount. Unless the clock is frozen it keeps
+/// running from there.
+pub fn set_time(nanos: u64) {
+    update(|state| {
+        let now = state.read(INCLUDING, state.base()[INCLUDING]);
+        state.shift(i128::from(nanos) - i128::from(now));
+    });
+}
+
+/// Moves both clocks forward by `duration`, as if that much time had passed.
+pub fn advance(duration: Duration) {
+    let nanos: i128 = duration.as_nanos().try_into().unwrap_or(i128::MAX);
+    update(|state| state.shift(nanos));
+}
+
+/// Stops the clocks. They only move with [`set_time`] and [`advance`] until [`unfreeze`].
+pub fn freeze() {
+    update(|state| {
+        if state.frozen.is_none() {
+            state.frozen = Some(real());
+        }
+    });
+}
+
+/// Lets the clocks run again, continuing from where they were frozen.
+pub fn unfreeze() {
+    update(|state| {
+        if let Some(frozen) = state.frozen.take() {
+            let now = real();
+            for clock in [INCLUDING, EXCLUDING] {
+                state.offset[clock] += i128::from(frozen[clock]) - i128::from(now[clock]);
+            }
+        }
+    });
+}
+
+/// Goes back to the real clocks.
+///
+/// Timestamps taken from the virtual clock can't be compared to the real clock afterwards.
+pub fn reset() {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    *state = State {
+        offset: [0; 2],
+        frozen: None,
+    };
+    ACTIVE.store(false, Ordering::Release);
+}
+
+/// Whether the virtual clock replaces the real one.
+pub(crate) fn is_active() -> bool {
+    ACTIVE.load(Ordering::Acquire)
+}
+
+/// Moves a reading of the real suspend-including clock onto the virtual clock.
+pub(crate) fn including_suspend(real: u64) -> u64 {
+    if !is_active() {
+        return real;
+    }
+    let state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.read(INCLUDING, real)
+}
+
+/// Moves a reading of the real suspend-excluding clock onto the virtual clock.
+pub(crate) fn excluding_suspend(real: u64) -> u64 {
+    if !is_active() {
+        return real;
+    }
+    let state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.read(EXCLUDING, real)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets() {
+        let mut state = State {
+            offset: [0; 2],
+            frozen: None,
+        };
+        assert_eq!(100, state.read(INCLUDING, 100));
+
+        state.shift(50);
+        assert_eq!(150, state.read(INCLUDING, 100));
+        assert_eq!(90, state.read(EXCLUDING, 40));
+
+        state.frozen = Some([1_000, 500]);
+        assert_eq!(1_050, state.read(INCLUDING, 2_000));
+        assert_eq!(550, state.read(EXCLUDING, 2_000));
+
+        state.shift(-10_000);
+        assert_eq!(0, state.read(INCLUDING, 2_000));
+    }
+}
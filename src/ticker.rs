@@ -0,0 +1,129 @@
+use std::convert::TryInto;
+use std::time::Duration;
+
+/// What a [`Ticker`] does about ticks it missed, e.g. because the system was suspended.
+///
+/// A tick counts as missed when it's over before it ran: the following tick is already due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissedTicks {
+    /// Run all missed ticks right away, one after the other, then continue on schedule.
+    Burst,
+    /// Run one tick right away and continue at the next regular tick.
+    Skip,
+    /// Run one tick right away and start a new schedule, one period after it.
+    Delay,
+}
+
+/// Ticks every period on the suspend-including clock, blocking the current thread in between.
+///
+/// Ticks are deadlines on [`now`](crate::now), so time the system spends in suspend counts
+/// towards them, and they stay on their original schedule: work that took long doesn't
+/// make the next tick late. See [`MissedTicks`] for what happens after a suspend.
+///
+/// It's an endless iterator of the deadlines of its ticks:
+///
+/// ```
+/// # use std::time::Duration;
+/// use zeitstempel::{MissedTicks, Ticker};
+///
+/// let ticker = Ticker::every(Duration::from_millis(1)).missed_ticks(MissedTicks::Skip);
+/// for deadline in ticker.take(3) {
+///     assert!(zeitstempel::now() >= deadline);
+///     // ... upload ...
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ticker {
+    period: u64,
+    next: u64,
+    missed_ticks: MissedTicks,
+}
+
+impl Ticker {
+    /// Creates a ticker whose first tick is one `period` from now.
+    ///
+    /// It skips missed ticks, unless set otherwise with
+    /// [`missed_ticks`](Ticker::missed_ticks).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn every(period: Duration) -> Ticker {
+        let period = period.as_nanos().try_into().unwrap_or(u64::MAX);
+        assert!(period > 0, "period must be non-zero");
+
+        Ticker {
+            period,
+            next: crate::now().saturating_add(period),
+            missed_ticks: MissedTicks::Skip,
+        }
+    }
+
+    /// Sets what happens to missed ticks.
+    pub fn missed_ticks(mut self, missed_ticks: MissedTicks) -> Ticker {
+        self.missed_ticks = missed_ticks;
+        self
+    }
+
+    /// Returns the deadline of the next tick, a timestamp as returned by [`now`](crate::now).
+    pub fn next_deadline(&self) -> u64 {
+        self.next
+    }
+
+    /// Blocks until the next tick, returning its deadline.
+    pub fn tick(&mut self) -> u64 {
+        let deadline = self.next;
+        crate::sleep_until(deadline);
+        self.next = next_tick(deadline, crate::now(), self.period, self.missed_ticks);
+        deadline
+    }
+}
+
+impl Iterator for Ticker {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        Some(self.tick())
+    }
+}
+
+/// The deadline of the tick after the one due at `deadline`, which ran at `now`.
+fn next_tick(deadline: u64, now: u64, period: u64, missed_ticks: MissedTicks) -> u64 {
+    let late = now.saturating_sub(deadline);
+    if late < period {
+        return deadline.saturating_add(period);
+    }
+
+    match missed_ticks {
+        MissedTicks::Burst => deadline.saturating_add(period),
+        MissedTicks::Skip => deadline.saturating_add((late / period + 1).saturating_mul(period)),
+        MissedTicks::Delay => now.saturating_add(period),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn missed() {
+        // On time, every behavior stays on schedule.
+        for &missed in &[MissedTicks::Burst, MissedTicks::Skip, MissedTicks::Delay] {
+            assert_eq!(200, next_tick(100, 150, 100, missed));
+        }
+
+        // Three and a half periods late.
+        assert_eq!(200, next_tick(100, 450, 100, MissedTicks::Burst));
+        assert_eq!(500, next_tick(100, 450, 100, MissedTicks::Skip));
+        assert_eq!(550, next_tick(100, 450, 100, MissedTicks::Delay));
+    }
+
+    #[test]
+    fn ticks_on_schedule() {
+        let mut ticker = Ticker::every(Duration::from_millis(2));
+        let first = ticker.next_deadline();
+        assert_eq!(first, ticker.tick());
+        assert!(crate::now() >= first);
+        assert!(ticker.next_deadline() >= first + 2_000_000);
+    }
+}
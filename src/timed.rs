@@ -0,0 +1,144 @@
+//! Reporting for the `#[timed]` attribute, available with the `macros` feature.
+//!
+//! Functions annotated with `#[timed]` report their duration to a sink.
+//! Unless the attribute names a sink of its own, that's the global sink set with [`set_sink`],
+//! which decides where measurements end up: a logger, a tracing span or a metrics library.
+//!
+//! With the `apple-signpost` feature, every measurement is also emitted as an `os_signpost`
+//! interval on macOS and iOS, so it shows up in Instruments.
+//!
+//! Without the attribute, [`measure`] reports a closure to the global sink,
+//! and a [`TimedScope`] reports the rest of a scope to a closure of its own.
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+type GlobalSink = Box<dyn Fn(&'static str, Duration) + Send + Sync + 'static>;
+
+static SINK: Lazy<RwLock<Option<GlobalSink>>> = Lazy::new(|| RwLock::new(None));
+
+/// Sets the global sink receiving the name and duration of every `#[timed]` function.
+///
+/// Replaces any previously set sink.
+/// Measurements taken before a sink is set are discarded.
+pub fn set_sink<F>(sink: F)
+where
+    F: Fn(&'static str, Duration) + Send + Sync + 'static,
+{
+    *SINK.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(sink));
+}
+
+/// Runs `f` and reports its duration under `name` to the global sink, like `#[timed]`.
+///
+/// ```
+/// let sum = zeitstempel::timed::measure("sum", || (0..1000u64).sum::<u64>());
+/// assert_eq!(499_500, sum);
+/// ```
+pub fn measure<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+    let _guard = Guard::new(name, None);
+    f()
+}
+
+/// Passes the time from its creation until it's dropped to `sink`, in nanoseconds.
+///
+/// The time includes time the system spent in suspend.
+/// Instrumenting a function body takes one line:
+///
+/// ```
+/// fn handle_request() {
+///     let _timing = zeitstempel::timed::TimedScope::new(|nanos| {
+///         println!("handled request in {}ns", nanos);
+///     });
+///     // ...
+/// }
+/// # handle_request();
+/// ```
+#[must_use = "the scope is measured until the value is dropped"]
+pub struct TimedScope<F: FnOnce(u64)> {
+    start: u64,
+    sink: Option<F>,
+}
+
+impl<F: FnOnce(u64)> TimedScope<F> {
+    /// Starts measuring.
+    pub fn new(sink: F) -> TimedScope<F> {
+        TimedScope {
+            start: crate::now(),
+            sink: Some(sink),
+        }
+    }
+}
+
+impl<F: FnOnce(u64)> Drop for TimedScope<F> {
+    fn drop(&mut self) {
+        let elapsed = crate::now().saturating_sub(self.start);
+        if let Some(sink) = self.sink.take() {
+            sink(elapsed);
+        }
+    }
+}
+
+/// Measures from creation until drop. Created by the `#[timed]` attribute.
+#[doc(hidden)]
+pub struct Guard {
+    name: &'static str,
+    sink: Option<fn(&'static str, Duration)>,
+    start: u64,
+    #[cfg(all(
+        feature = "apple-signpost",
+        any(target_os = "macos", target_os = "ios"),
+        not(feature = "forbid-unsafe")
+    ))]
+    _interval: crate::signpost::Interval,
+}
+
+impl Guard {
+    #[doc(hidden)]
+    pub fn new(name: &'static str, sink: Option<fn(&'static str, Duration)>) -> Guard {
+        Guard {
+            name,
+            sink,
+            start: crate::now(),
+            #[cfg(all(
+                feature = "apple-signpost",
+                any(target_os = "macos", target_os = "ios"),
+                not(feature = "forbid-unsafe")
+            ))]
+            _interval: crate::signpost::Interval::begin(name),
+        }
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let elapsed = Duration::from_nanos(crate::now().saturating_sub(self.start));
+        match self.sink {
+            Some(sink) => sink(self.name, elapsed),
+            None => {
+                if let Some(sink) = &*SINK.read().unwrap_or_else(|e| e.into_inner()) {
+                    sink(self.name, elapsed);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::Cell;
+    use std::thread;
+
+    #[test]
+    fn scope_reports_on_drop() {
+        let reported = Cell::new(None);
+        {
+            let _scope = TimedScope::new(|nanos| reported.set(Some(nanos)));
+            thread::sleep(Duration::from_millis(2));
+            assert_eq!(None, reported.get());
+        }
+        assert!(reported.get().unwrap() >= 2_000_000);
+    }
+}
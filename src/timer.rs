@@ -0,0 +1,340 @@
+//! Async timers on the suspend-including clock, for [tokio], with the `async-tokio` feature.
+//!
+//! Tokio's own timers are measured on a clock that (depending on the operating system)
+//! doesn't advance while the machine is suspended.
+//! A heartbeat every 30 seconds waits 30 seconds *awake* with them.
+//! The timers here measure their deadlines on [`now`](crate::now) instead:
+//! a deadline that passed during suspend fires right after the system resumes.
+//!
+//! On Linux and Android the futures wait on a `CLOCK_BOOTTIME` timerfd registered with the
+//! tokio reactor. Elsewhere they wait on tokio timers in slices of at most 250 ms,
+//! so a deadline passed during suspend is noticed within that time after resuming.
+//!
+//! The futures must be polled within a tokio runtime with the time and IO drivers enabled.
+//!
+//! ```
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use std::time::Duration;
+//! use zeitstempel::timer;
+//!
+//! let mut heartbeat = timer::interval(Duration::from_millis(1));
+//! heartbeat.tick().await;
+//! heartbeat.tick().await;
+//!
+//! let result = timer::timeout(Duration::from_millis(1), std::future::pending::<()>()).await;
+//! assert!(result.is_err());
+//! # }
+//! ```
+//!
+//! [tokio]: https://docs.rs/tokio
+
+use std::fmt;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use crate::wait::{as_nanos, MAX_SLICE};
+
+/// Waits until `deadline`, a timestamp as returned by [`now`](crate::now).
+pub fn sleep_until(deadline: u64) -> Sleep {
+    Sleep {
+        deadline,
+        inner: None,
+    }
+}
+
+/// Waits for `duration`, including time the system spends in suspend.
+pub fn sleep(duration: Duration) -> Sleep {
+    sleep_until(crate::now().saturating_add(as_nanos(duration)))
+}
+
+/// A future completing at a deadline on the suspend-including clock,
+/// returned by [`sleep`] and [`sleep_until`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Sleep {
+    deadline: u64,
+    /// Set up on the first poll, when the runtime is known to be there.
+    inner: Option<Inner>,
+}
+
+#[derive(Debug)]
+enum Inner {
+    #[cfg(all(
+        any(target_os = "linux", target_os = "android"),
+        not(feature = "forbid-unsafe")
+    ))]
+    TimerFd(timerfd::Timer),
+    Slices(Pin<Box<tokio::time::Sleep>>),
+}
+
+impl Inner {
+    fn new(deadline: u64) -> Inner {
+        #[cfg(all(
+            any(target_os = "linux", target_os = "android"),
+            not(feature = "forbid-unsafe")
+        ))]
+        {
+            if let Some(timer) = timerfd::Timer::new(deadline) {
+                return Inner::TimerFd(timer);
+            }
+        }
+
+        Inner::Slices(Box::pin(tokio::time::sleep(slice(deadline))))
+    }
+}
+
+/// The time to wait on a tokio timer until the clock is checked again.
+fn slice(deadline: u64) -> Duration {
+    Duration::from_nanos(deadline.saturating_sub(crate::now())).min(MAX_SLICE)
+}
+
+impl Sleep {
+    /// The deadline, a timestamp as returned by [`now`](crate::now).
+    pub fn deadline(&self) -> u64 {
+        self.deadline
+    }
+
+    /// Returns whether the deadline passed.
+    pub fn is_elapsed(&self) -> bool {
+        crate::now() >= self.deadline
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let deadline = self.deadline;
+        loop {
+            if crate::now() >= deadline {
+                return Poll::Ready(());
+            }
+
+            match &mut self.inner {
+                None => self.inner = Some(Inner::new(deadline)),
+                #[cfg(all(
+                    any(target_os = "linux", target_os = "android"),
+                    not(feature = "forbid-unsafe")
+                ))]
+                Some(Inner::TimerFd(timer)) => {
+                    if timer.poll_fired(cx).is_pending() {
+                        return Poll::Pending;
+                    }
+                    // The timer fired, but the clock says it's early: it doesn't run on
+                    // `CLOCK_BOOTTIME`, e.g. with the `testing` feature. Fall back to slices.
+                    if crate::now() < deadline {
+                        let sleep = tokio::time::sleep(slice(deadline));
+                        self.inner = Some(Inner::Slices(Box::pin(sleep)));
+                    }
+                }
+                Some(Inner::Slices(sleep)) => {
+                    if sleep.as_mut().poll(cx).is_pending() {
+                        return Poll::Pending;
+                    }
+                    let next = tokio::time::Instant::now() + slice(deadline);
+                    sleep.as_mut().reset(next);
+                }
+            }
+        }
+    }
+}
+
+/// Requires `future` to complete within `duration`, including time the system spends in
+/// suspend.
+pub fn timeout<F: Future>(duration: Duration, future: F) -> Timeout<F> {
+    Timeout {
+        future: Box::pin(future),
+        sleep: sleep(duration),
+    }
+}
+
+/// A future requiring another one to complete before a deadline, returned by [`timeout`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Timeout<F> {
+    future: Pin<Box<F>>,
+    sleep: Sleep,
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(output) = self.future.as_mut().poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        Pin::new(&mut self.sleep)
+            .poll(cx)
+            .map(|()| Err(Elapsed(())))
+    }
+}
+
+/// The error of a [`Timeout`] whose deadline passed first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed(());
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "deadline has elapsed")
+    }
+}
+
+impl std::error::Error for Elapsed {}
+
+/// Ticks every `period`, including time the system spends in suspend.
+/// The first tick completes immediately.
+///
+/// The interval stays on its original schedule, like a [`Scheduler`](crate::Scheduler) job
+/// with [`CatchUp::RunOnce`](crate::CatchUp::RunOnce): ticks that were missed, e.g. during
+/// suspend, complete once right away and the interval continues at the next regular tick.
+///
+/// # Panics
+///
+/// Panics if `period` is zero.
+pub fn interval(period: Duration) -> Interval {
+    let period = as_nanos(period);
+    assert!(period > 0, "period must be non-zero");
+
+    Interval {
+        period,
+        sleep: sleep_until(crate::now()),
+    }
+}
+
+/// A stream of ticks on the suspend-including clock, returned by [`interval`].
+#[derive(Debug)]
+pub struct Interval {
+    period: u64,
+    sleep: Sleep,
+}
+
+impl Interval {
+    /// Completes at the next tick, returning its deadline.
+    pub async fn tick(&mut self) -> u64 {
+        poll_fn(|cx| self.poll_tick(cx)).await
+    }
+
+    /// Polls for the next tick, returning its deadline.
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<u64> {
+        if Pin::new(&mut self.sleep).poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+
+        let deadline = self.sleep.deadline;
+        let late = crate::now().saturating_sub(deadline);
+        let next = deadline.saturating_add((late / self.period + 1).saturating_mul(self.period));
+        self.sleep = sleep_until(next);
+        Poll::Ready(deadline)
+    }
+
+    /// The length of the interval.
+    pub fn period(&self) -> Duration {
+        Duration::from_nanos(self.period)
+    }
+}
+
+#[cfg(all(
+    any(target_os = "linux", target_os = "android"),
+    not(feature = "forbid-unsafe")
+))]
+mod timerfd {
+    //! A `CLOCK_BOOTTIME` timerfd, registered with the tokio reactor.
+
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::task::{Context, Poll};
+
+    use tokio::io::unix::AsyncFd;
+
+    use crate::wait::timerfd::{arm, create};
+
+    #[derive(Debug)]
+    pub(crate) struct Timer(AsyncFd<OwnedFd>);
+
+    impl Timer {
+        /// Arms a timer for `deadline`,
+        /// `None` if there's no `CLOCK_BOOTTIME` or no reactor to register it with.
+        pub(crate) fn new(deadline: u64) -> Option<Timer> {
+            let fd = unsafe { OwnedFd::from_raw_fd(create()?) };
+            if !arm(fd.as_raw_fd(), Some(deadline)) {
+                return None;
+            }
+
+            AsyncFd::new(fd).ok().map(Timer)
+        }
+
+        /// Completes once the timer fired. Errors count as fired: callers re-check the clock.
+        pub(crate) fn poll_fired(&self, cx: &mut Context<'_>) -> Poll<()> {
+            loop {
+                let mut guard = match self.0.poll_read_ready(cx) {
+                    Poll::Ready(Ok(guard)) => guard,
+                    Poll::Ready(Err(_)) => return Poll::Ready(()),
+                    Poll::Pending => return Poll::Pending,
+                };
+
+                let mut expirations = 0u64;
+                let read = unsafe {
+                    libc::read(
+                        self.0.as_raw_fd(),
+                        &mut expirations as *mut u64 as *mut libc::c_void,
+                        8,
+                    )
+                };
+                if read == 8 {
+                    return Poll::Ready(());
+                }
+                guard.clear_ready();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn sleeps_until_deadline() {
+        runtime().block_on(async {
+            let start = crate::now();
+            sleep(Duration::from_millis(5)).await;
+            assert_elapsed!(start, >= Duration::from_millis(5));
+
+            // Deadlines in the past complete right away.
+            sleep_until(0).await;
+        });
+    }
+
+    #[test]
+    fn times_out() {
+        runtime().block_on(async {
+            let pending = timeout(Duration::from_millis(2), std::future::pending::<()>());
+            assert_eq!(Err(Elapsed(())), pending.await);
+
+            let ready = timeout(Duration::from_secs(60), async { 7 });
+            assert_eq!(Ok(7), ready.await);
+        });
+    }
+
+    #[test]
+    fn ticks_on_schedule() {
+        runtime().block_on(async {
+            let mut interval = interval(Duration::from_millis(2));
+            let first = interval.tick().await;
+            let second = interval.tick().await;
+            let third = interval.tick().await;
+
+            assert_eq!(2_000_000, second - first);
+            assert_eq!(4_000_000, third - first);
+            assert!(crate::now() >= third);
+        });
+    }
+}
@@ -0,0 +1,78 @@
+//! The Windows timer resolution, on Windows only.
+//!
+//! Windows updates the interrupt time, and with it [`now`](crate::now), once per clock
+//! interrupt. By default that's every 15.625 ms, but any process can raise the interrupt
+//! frequency for the whole system, e.g. browsers and media players playing video often do,
+//! down to about 0.5 ms. That's why deltas are quantized to 15.6 ms on some machines and to
+//! 1 ms on others, or change between runs on the same machine.
+//!
+//! With the `precise-interrupt-time` feature, timestamps are interpolated and aren't affected.
+
+use std::time::Duration;
+
+#[link(name = "ntdll")]
+extern "system" {
+    /// Gets the range of timer resolutions and the current one, in 100 ns units.
+    /// The "minimum" resolution is the longest period, the "maximum" the shortest.
+    fn NtQueryTimerResolution(
+        minimum_resolution: *mut u32,
+        maximum_resolution: *mut u32,
+        current_resolution: *mut u32,
+    ) -> i32;
+}
+
+/// Windows counts time in a system time unit of 100 nanoseconds.
+const SYSTEM_TIME_UNIT: u64 = 100;
+
+/// The timer resolutions supported by the system and the one currently in effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerResolution {
+    /// The longest interrupt period, used when no process asked for a higher resolution.
+    pub coarsest: Duration,
+    /// The shortest interrupt period any process can ask for.
+    pub finest: Duration,
+    /// The interrupt period currently in effect.
+    pub current: Duration,
+}
+
+impl TimerResolution {
+    /// Returns whether a process, this one or another, has raised the timer resolution above
+    /// the default.
+    ///
+    /// Deltas measured now have a finer granularity than they would on an idle system.
+    pub fn is_raised(&self) -> bool {
+        self.current < self.coarsest
+    }
+}
+
+/// Returns the current timer resolution, queried with `NtQueryTimerResolution`.
+///
+/// Returns `None` if the query fails.
+pub fn current() -> Option<TimerResolution> {
+    let mut coarsest = 0;
+    let mut finest = 0;
+    let mut current = 0;
+    let status = unsafe { NtQueryTimerResolution(&mut coarsest, &mut finest, &mut current) };
+    if status < 0 {
+        return None;
+    }
+
+    let duration = |units: u32| Duration::from_nanos(u64::from(units) * SYSTEM_TIME_UNIT);
+    Some(TimerResolution {
+        coarsest: duration(coarsest),
+        finest: duration(finest),
+        current: duration(current),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolution_in_range() {
+        let resolution = current().unwrap();
+        assert!(resolution.finest <= resolution.current);
+        assert!(resolution.current <= resolution.coarsest);
+    }
+}
@@ -0,0 +1,356 @@
+use core::convert::TryInto;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
+#[cfg(feature = "std")]
+use std::hash::{BuildHasher, Hasher};
+
+#[cfg(feature = "std")]
+use crate::boot;
+
+/// Crockford's base32 alphabet. It's in ASCII order, so encodings sort like their values.
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// 13 base32 digits hold the 64 bits of a timestamp.
+const SORTABLE_LEN: usize = 13;
+
+/// About 30 years, in nanoseconds.
+const FAR_FUTURE: u64 = 30 * 365 * 24 * 60 * 60 * 1_000_000_000;
+
+/// A timestamp from the suspend-including clock, in nanoseconds.
+///
+/// It's the type-safe variant of the `u64` returned by [`now`](crate::now):
+/// subtracting two timestamps results in a [`Duration`], and timestamps aren't
+/// accidentally mixed up with unrelated integers.
+/// Convert with [`From`] where a plain integer is needed, e.g. for serialization.
+/// With the `serde` feature it serializes as that integer.
+///
+/// ```
+/// # use std::{thread, time::Duration};
+/// use zeitstempel::Timestamp;
+///
+/// let start = Timestamp::now();
+/// thread::sleep(Duration::from_millis(2));
+/// assert!(Timestamp::now() - start >= Duration::from_millis(2));
+///
+/// let raw: u64 = start.into();
+/// assert_eq!(start, Timestamp::from(raw));
+/// ```
+///
+/// Adding durations saturates at [`Timestamp::MAX`],
+/// so it can stand for "never" in timer wheels and deadline queues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(pub(crate) u64);
+
+impl Timestamp {
+    /// The latest representable timestamp.
+    ///
+    /// Every other timestamp compares as earlier, and arithmetic saturates at it.
+    /// Use it to represent a deadline that is never reached.
+    pub const MAX: Timestamp = Timestamp(u64::MAX);
+
+    /// Returns a timestamp corresponding to "now", like [`now`](crate::now).
+    pub fn now() -> Timestamp {
+        Timestamp(crate::now())
+    }
+
+    /// Returns a timestamp about 30 years from now.
+    ///
+    /// Unlike [`Timestamp::MAX`] there's plenty of room to add durations to it,
+    /// so it can be used where a real deadline is needed that's practically never reached.
+    pub fn far_future() -> Timestamp {
+        Timestamp(crate::now()).saturating_add(Duration::from_nanos(FAR_FUTURE))
+    }
+
+    /// Returns whether this is [`Timestamp::MAX`].
+    pub fn is_max(self) -> bool {
+        self == Timestamp::MAX
+    }
+
+    /// Adds a duration, saturating at [`Timestamp::MAX`].
+    pub fn saturating_add(self, duration: Duration) -> Timestamp {
+        let duration = duration.as_nanos().try_into().unwrap_or(u64::MAX);
+        Timestamp(self.0.saturating_add(duration))
+    }
+
+    /// Subtracts a duration, saturating at zero.
+    ///
+    /// [`Timestamp::MAX`] stays [`Timestamp::MAX`]: "never" minus some time is still "never".
+    pub fn saturating_sub(self, duration: Duration) -> Timestamp {
+        if self.is_max() {
+            return self;
+        }
+
+        let duration = duration.as_nanos().try_into().unwrap_or(u64::MAX);
+        Timestamp(self.0.saturating_sub(duration))
+    }
+
+    /// Subtracts a duration, returning `None` if the result would be before zero.
+    ///
+    /// [`Timestamp::MAX`] stays [`Timestamp::MAX`], like with
+    /// [`saturating_sub`](Self::saturating_sub).
+    pub fn checked_sub(self, duration: Duration) -> Option<Timestamp> {
+        if self.is_max() {
+            return Some(self);
+        }
+
+        let duration = duration.as_nanos().try_into().ok()?;
+        self.0.checked_sub(duration).map(Timestamp)
+    }
+
+    /// Returns the time elapsed from `earlier` to this timestamp,
+    /// or `None` if `earlier` is later than this timestamp.
+    pub fn checked_duration_since(self, earlier: Timestamp) -> Option<Duration> {
+        self.0.checked_sub(earlier.0).map(Duration::from_nanos)
+    }
+
+    /// Returns the time elapsed from `earlier` to this timestamp,
+    /// or a zero duration if `earlier` is later than this timestamp.
+    ///
+    /// This is the same as subtracting the timestamps.
+    pub fn saturating_duration_since(self, earlier: Timestamp) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or_default()
+    }
+
+    /// Encodes the timestamp as 13 characters of Crockford's base32, e.g. `000Z3QJRSGX1C`.
+    ///
+    /// All encodings have the same length and sort lexicographically in the order of the
+    /// timestamps, so they can serve as database or object store keys.
+    #[cfg(feature = "std")]
+    pub fn to_sortable_string(self) -> String {
+        let mut encoded = String::with_capacity(SORTABLE_LEN);
+        for digit in (0..SORTABLE_LEN).rev() {
+            // The first digit only holds the 4 most significant bits.
+            let value = (self.0 >> (digit * 5)) & 0x1f;
+            encoded.push(ALPHABET[value as usize] as char);
+        }
+        encoded
+    }
+
+    /// Encodes the timestamp like [`to_sortable_string`](Timestamp::to_sortable_string),
+    /// followed by the boot ID and a random suffix: `<timestamp>-<boot>-<random>`.
+    ///
+    /// Keys have a fixed length of 54 characters and sort in capture order within a boot.
+    /// The boot ID tells apart keys from different boots, whose timestamps may overlap,
+    /// and the random part keys created at the same nanosecond.
+    /// Where the operating system provides no boot ID it's all zeros.
+    ///
+    /// The random part isn't cryptographically secure, it only serves to avoid collisions.
+    #[cfg(feature = "std")]
+    pub fn to_sortable_key(self) -> String {
+        let boot_id = boot::boot_id().unwrap_or(0);
+        let mut random = RandomState::new().build_hasher();
+        random.write_u64(self.0);
+
+        let mut key = self.to_sortable_string();
+        key.push('-');
+        key.push_str(&Timestamp((boot_id >> 64) as u64).to_sortable_string());
+        key.push_str(&Timestamp(boot_id as u64).to_sortable_string());
+        key.push('-');
+        key.push_str(&Timestamp(random.finish()).to_sortable_string());
+        key
+    }
+
+    /// Decodes the timestamp from [`to_sortable_string`](Timestamp::to_sortable_string) or
+    /// [`to_sortable_key`](Timestamp::to_sortable_key), ignoring the rest of a key.
+    ///
+    /// Lowercase letters are accepted. Returns `None` for anything else.
+    pub fn from_sortable_string(s: &str) -> Option<Timestamp> {
+        let (encoded, rest) = (s.get(..SORTABLE_LEN)?, &s[SORTABLE_LEN..]);
+        if !rest.is_empty() && !rest.starts_with('-') {
+            return None;
+        }
+
+        let mut value: u64 = 0;
+        for (i, c) in encoded.bytes().enumerate() {
+            let digit = ALPHABET.iter().position(|&a| a == c.to_ascii_uppercase())? as u64;
+            if i == 0 && digit > 0xf {
+                return None;
+            }
+            value = value << 5 | digit;
+        }
+        Some(Timestamp(value))
+    }
+}
+
+/// Saturates at [`Timestamp::MAX`] instead of overflowing.
+impl Add<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    fn add(self, duration: Duration) -> Timestamp {
+        self.saturating_add(duration)
+    }
+}
+
+impl AddAssign<Duration> for Timestamp {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+/// Saturates at zero, see [`Timestamp::saturating_sub`].
+impl Sub<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    fn sub(self, duration: Duration) -> Timestamp {
+        self.saturating_sub(duration)
+    }
+}
+
+impl SubAssign<Duration> for Timestamp {
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = *self - duration;
+    }
+}
+
+/// Returns the time elapsed from `earlier` to `self`, or zero if `earlier` is later.
+impl Sub for Timestamp {
+    type Output = Duration;
+
+    fn sub(self, earlier: Timestamp) -> Duration {
+        self.saturating_duration_since(earlier)
+    }
+}
+
+impl From<u64> for Timestamp {
+    fn from(nanos: u64) -> Timestamp {
+        Timestamp(nanos)
+    }
+}
+
+impl From<Timestamp> for u64 {
+    fn from(ts: Timestamp) -> u64 {
+        ts.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn operators() {
+        let mut ts = Timestamp(1_000);
+        ts += Duration::from_nanos(500);
+        assert_eq!(Timestamp(1_500), ts);
+        ts -= Duration::from_nanos(2_000);
+        assert_eq!(Timestamp(0), ts);
+        assert_eq!(Timestamp(500), Timestamp(1_000) - Duration::from_nanos(500));
+        assert_eq!(Timestamp::MAX, Timestamp::MAX - Duration::from_secs(1));
+    }
+
+    #[test]
+    fn duration_since() {
+        let earlier = Timestamp(1_000);
+        let later = Timestamp(3_000);
+        assert_eq!(
+            Some(Duration::from_nanos(2_000)),
+            later.checked_duration_since(earlier)
+        );
+        assert_eq!(None, earlier.checked_duration_since(later));
+        assert_eq!(Duration::ZERO, earlier.saturating_duration_since(later));
+        assert_eq!(later - earlier, later.saturating_duration_since(earlier));
+    }
+
+    #[test]
+    fn saturates_at_max() {
+        let ts = Timestamp(u64::MAX - 1);
+        assert_eq!(Timestamp::MAX, ts + Duration::from_secs(1));
+        assert_eq!(Timestamp::MAX, Timestamp::MAX + Duration::from_secs(1));
+        assert_eq!(
+            Timestamp::MAX,
+            Timestamp::MAX.saturating_sub(Duration::from_secs(1))
+        );
+        assert_eq!(
+            Some(Timestamp::MAX),
+            Timestamp::MAX.checked_sub(Duration::from_secs(1))
+        );
+        assert_eq!(
+            Some(Timestamp::MAX),
+            Timestamp::MAX.checked_sub(Duration::MAX)
+        );
+        assert_eq!(
+            Timestamp(0),
+            Timestamp(1).saturating_sub(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn differences() {
+        let earlier = Timestamp::from(1_000);
+        let later = earlier + Duration::from_nanos(500);
+
+        assert_eq!(Duration::from_nanos(500), later - earlier);
+        assert_eq!(Duration::from_secs(0), earlier - later);
+        assert_eq!(Some(earlier), later.checked_sub(Duration::from_nanos(500)));
+        assert_eq!(None, earlier.checked_sub(Duration::from_nanos(1_001)));
+        assert_eq!(1_500, u64::from(later));
+        #[cfg(feature = "std")]
+        assert!(Timestamp::now() > earlier);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn sortable_strings() {
+        let timestamps = [0, 1, 31, 32, 1_000_000_000, u64::MAX - 1, u64::MAX];
+        let encoded: Vec<String> = timestamps
+            .iter()
+            .map(|&ts| Timestamp(ts).to_sortable_string())
+            .collect();
+
+        assert_eq!("0000000000000", encoded[0]);
+        assert_eq!("FZZZZZZZZZZZZ", encoded[6]);
+        assert!(encoded.windows(2).all(|pair| pair[0] < pair[1]));
+        for (&ts, encoded) in timestamps.iter().zip(&encoded) {
+            assert_eq!(
+                Some(Timestamp(ts)),
+                Timestamp::from_sortable_string(encoded)
+            );
+        }
+
+        let key = Timestamp(42).to_sortable_key();
+        assert_eq!(54, key.len());
+        assert_eq!(Some(Timestamp(42)), Timestamp::from_sortable_string(&key));
+        assert!(key < Timestamp(43).to_sortable_key());
+
+        assert_eq!(None, Timestamp::from_sortable_string("G000000000000"));
+        assert_eq!(None, Timestamp::from_sortable_string("000000000000U"));
+        assert_eq!(None, Timestamp::from_sortable_string("0000000000000x"));
+    }
+
+    #[test]
+    fn far_future() {
+        let far = Timestamp::far_future();
+        assert!(!far.is_max());
+        assert!(far < Timestamp::MAX);
+        assert!(far + Duration::from_secs(3600) > far);
+    }
+}
+
+/// Proofs that the arithmetic never panics, checked with `cargo kani`.
+#[cfg(kani)]
+mod verification {
+    use super::*;
+
+    fn any_duration() -> Duration {
+        Duration::new(kani::any(), kani::any::<u32>() % 1_000_000_000)
+    }
+
+    #[kani::proof]
+    fn saturating_add_never_panics() {
+        let ts = Timestamp(kani::any());
+        let later = ts + any_duration();
+        assert!(later >= ts);
+    }
+
+    #[kani::proof]
+    fn saturating_sub_never_panics() {
+        let ts = Timestamp(kani::any());
+        let earlier = ts.saturating_sub(any_duration());
+        assert!(earlier <= ts);
+        if ts.is_max() {
+            assert!(earlier.is_max());
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Timestamps and span durations for [tracing], available with the `tracing` feature.
+//!
+//! [`Uptime`] formats the time of log lines for `tracing_subscriber::fmt`,
+//! [`SpanTimings`] reports how long spans were open.
+//! Both read [`now`](crate::now), so they include time spent in suspend and are consistent with
+//! the crate's other measurements, e.g. of `#[timed]` functions.
+//!
+//! ```
+//! use tracing_subscriber::layer::SubscriberExt;
+//! use zeitstempel::trace::{SpanTimings, Uptime};
+//!
+//! let subscriber = tracing_subscriber::registry()
+//!     .with(tracing_subscriber::fmt::layer().with_timer(Uptime::default()))
+//!     .with(SpanTimings::new(|name, duration| println!("{} took {:?}", name, duration)));
+//! tracing::subscriber::with_default(subscriber, || {
+//!     let _upload = tracing::info_span!("upload").entered();
+//!     tracing::info!("uploading");
+//! });
+//! ```
+//!
+//! [tracing]: https://docs.rs/tracing
+
+use std::fmt;
+use std::time::Duration;
+
+use tracing_core::span::{Attributes, Id};
+use tracing_core::Subscriber;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::time::FormatTime;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Formats the time since it was created, like `tracing_subscriber`'s own `Uptime`,
+/// but including time the system spent in suspend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Uptime {
+    start: u64,
+}
+
+impl Uptime {
+    /// Formats the time since the clock's starting point instead, usually the last boot.
+    pub fn since_boot() -> Uptime {
+        Uptime { start: 0 }
+    }
+}
+
+impl Default for Uptime {
+    /// Formats the time since now.
+    fn default() -> Uptime {
+        Uptime {
+            start: crate::now(),
+        }
+    }
+}
+
+impl FormatTime for Uptime {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let elapsed = Duration::from_nanos(crate::now().saturating_sub(self.start));
+        write!(w, "{:4}.{:09}s", elapsed.as_secs(), elapsed.subsec_nanos())
+    }
+}
+
+/// When a span was created, kept in its extensions.
+struct Opened(u64);
+
+/// A layer passing the name of every closed span and how long it was open to a sink.
+pub struct SpanTimings<F> {
+    sink: F,
+}
+
+impl<F> SpanTimings<F>
+where
+    F: Fn(&'static str, Duration) + Send + Sync + 'static,
+{
+    /// Creates a layer reporting to `sink`.
+    pub fn new(sink: F) -> SpanTimings<F> {
+        SpanTimings { sink }
+    }
+}
+
+impl<S, F> Layer<S> for SpanTimings<F>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    F: Fn(&'static str, Duration) + Send + Sync + 'static,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Opened(crate::now()));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(&id) {
+            Some(span) => span,
+            None => return,
+        };
+        let opened = span.extensions().get::<Opened>().map(|opened| opened.0);
+        if let Some(opened) = opened {
+            let open = Duration::from_nanos(crate::now().saturating_sub(opened));
+            (self.sink)(span.name(), open);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn reports_closed_spans() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let layer = {
+            let reported = Arc::clone(&reported);
+            SpanTimings::new(move |name, duration| reported.lock().unwrap().push((name, duration)))
+        };
+
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("work").entered();
+            thread::sleep(Duration::from_millis(2));
+        });
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(1, reported.len());
+        assert_eq!("work", reported[0].0);
+        assert!(reported[0].1 >= Duration::from_millis(2));
+    }
+
+    #[test]
+    fn formats_uptime() {
+        let mut formatted = String::new();
+        Uptime::since_boot()
+            .format_time(&mut Writer::new(&mut formatted))
+            .unwrap();
+        assert!(formatted.ends_with('s'));
+        assert_eq!(
+            Some('.'),
+            formatted.trim_start().chars().find(|c| !c.is_ascii_digit())
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Timestamp implementation reading the invariant TSC on x86-64 Linux, with the `tsc` feature.
+//!
+//! `rdtsc` reads the time stamp counter without a system call.
+//! It's used if the CPU reports an invariant TSC, ticking at a constant rate across all cores
+//! and power states, and the kernel didn't reject it as its clocksource.
+//! The frequency is measured against `CLOCK_MONOTONIC` on first use, which takes about
+//! 10 milliseconds. See [`calibration`](crate::calibration) for how ticks become
+//! `CLOCK_BOOTTIME` nanoseconds.
+//!
+//! Otherwise this falls back to the regular Linux implementation.
+
+use std::arch::x86_64::{__cpuid, _rdtsc};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::calibration::{self, Calibrated};
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::linux;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::{bracketed, Readings};
+
+/// The converted TSC, `None` if it can't be trusted.
+static TSC: Lazy<Option<Calibrated>> =
+    Lazy::new(|| usable().then(|| Calibrated::new(rdtsc, calibration::measure_frequency(rdtsc))));
+
+fn rdtsc() -> u64 {
+    unsafe { _rdtsc() }
+}
+
+/// Whether the CPU reports an invariant TSC, in CPUID leaf `0x8000_0007`,
+/// and the kernel uses it as well, where it tells.
+fn usable() -> bool {
+    let max_leaf = __cpuid(0x8000_0000).eax;
+    if max_leaf < 0x8000_0007 {
+        return false;
+    }
+    let invariant = __cpuid(0x8000_0007).edx & (1 << 8) != 0;
+
+    let clocksource =
+        std::fs::read_to_string("/sys/devices/system/clocksource/clocksource0/current_clocksource");
+    invariant && clocksource.map_or(true, |source| source.trim() == "tsc")
+}
+
+/// The TSC converted to `CLOCK_BOOTTIME` nanoseconds, or `CLOCK_BOOTTIME` itself.
+pub fn now_including_suspend() -> u64 {
+    match &*TSC {
+        Some(tsc) => tsc.now(),
+        None => linux::now_including_suspend(),
+    }
+}
+
+/// Reading the TSC can't fail, only the fallback can.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    match &*TSC {
+        Some(tsc) => Ok(tsc.now()),
+        None => linux::try_now_including_suspend(),
+    }
+}
+
+/// `CLOCK_MONOTONIC`, as the TSC would need its own pairing for it.
+pub fn now_excluding_suspend() -> u64 {
+    linux::now_excluding_suspend()
+}
+
+/// Reads both clocks back-to-back, or both Linux clocks.
+pub fn now_both() -> Readings {
+    match &*TSC {
+        Some(_) => bracketed(now_including_suspend, now_excluding_suspend),
+        None => linux::now_both(),
+    }
+}
+
+/// The converted TSC counts nanoseconds.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    match &*TSC {
+        Some(tsc) => RawTimestamp {
+            value: tsc.now(),
+            unit: TickUnit::Nanoseconds,
+        },
+        None => linux::raw_now_including_suspend(),
+    }
+}
+
+/// The converted TSC and the fallback both count nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// The TSC, with the length of a tick, or `CLOCK_BOOTTIME`.
+pub fn clock_info() -> ClockInfo {
+    match &*TSC {
+        Some(tsc) => ClockInfo {
+            name: "TSC",
+            resolution: Some(tsc.tick_length().max(Duration::from_nanos(1))),
+            includes_suspend: true,
+        },
+        None => linux::clock_info(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn close_to_boottime() {
+        let tsc = now_including_suspend();
+        let boottime = linux::now_including_suspend();
+        assert!(boottime.max(tsc) - boottime.min(tsc) < 1_000_000);
+    }
+}
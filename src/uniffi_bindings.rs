@@ -0,0 +1,118 @@
+//! The interface for Kotlin and Swift bindings generated by [UniFFI], with the `uniffi` feature.
+//!
+//! Build the library with `cargo rustc --release --lib --features uniffi --crate-type cdylib`
+//! and generate the bindings from it with `uniffi-bindgen generate --library`.
+//! The timestamps are the same as those of the Rust functions, so they can be compared
+//! across languages within a process, and across boots through [`BootTimestamp`].
+//!
+//! [UniFFI]: https://mozilla.github.io/uniffi-rs/
+
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::boot::Boot;
+use crate::Timestamp;
+
+/// A timestamp together with the boot it was taken in, see [`crate::BootTimestamp`].
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct BootTimestamp {
+    /// The timestamp from [`now`](crate::now), in nanoseconds.
+    pub timestamp: u64,
+    /// The operating system's boot ID as 32 hex digits, if it provides one.
+    pub boot_id: Option<String>,
+    /// The boot time estimated from the wall clock, in nanoseconds since the Unix epoch.
+    pub boot_time: u64,
+}
+
+impl From<crate::BootTimestamp> for BootTimestamp {
+    fn from(ts: crate::BootTimestamp) -> BootTimestamp {
+        BootTimestamp {
+            timestamp: ts.timestamp.into(),
+            boot_id: ts.boot.id.map(|id| format!("{:032x}", id)),
+            boot_time: ts.boot.time,
+        }
+    }
+}
+
+impl From<BootTimestamp> for crate::BootTimestamp {
+    /// A malformed boot ID is ignored, the boot is identified by its boot time then.
+    fn from(ts: BootTimestamp) -> crate::BootTimestamp {
+        crate::BootTimestamp {
+            timestamp: Timestamp::from(ts.timestamp),
+            boot: Boot {
+                id: ts.boot_id.and_then(|id| u128::from_str_radix(&id, 16).ok()),
+                time: ts.boot_time,
+            },
+        }
+    }
+}
+
+/// Errors comparing timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Error)]
+pub enum TimestampError {
+    /// The timestamps were taken during different boots, see [`crate::DifferentBoot`].
+    DifferentBoot,
+}
+
+impl fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampError::DifferentBoot => crate::DifferentBoot.fmt(f),
+        }
+    }
+}
+
+impl Error for TimestampError {}
+
+/// Returns a timestamp corresponding to "now", see [`crate::now`].
+#[uniffi::export]
+pub fn now() -> u64 {
+    crate::now()
+}
+
+/// Returns a timestamp without the time spent in suspend, see [`crate::now_excluding_suspend`].
+#[uniffi::export]
+pub fn now_excluding_suspend() -> u64 {
+    crate::now_excluding_suspend()
+}
+
+/// Returns a timestamp corresponding to "now", in the current boot.
+#[uniffi::export]
+pub fn boot_timestamp_now() -> BootTimestamp {
+    crate::BootTimestamp::now().into()
+}
+
+/// Returns the time elapsed from `earlier` to `later`, see
+/// [`crate::BootTimestamp::duration_since`].
+#[uniffi::export]
+pub fn boot_timestamp_duration_since(
+    later: BootTimestamp,
+    earlier: BootTimestamp,
+) -> Result<Duration, TimestampError> {
+    crate::BootTimestamp::from(later)
+        .duration_since(&earlier.into())
+        .map_err(|_| TimestampError::DifferentBoot)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn boot_timestamps() {
+        let earlier = boot_timestamp_now();
+        let later = boot_timestamp_now();
+        assert!(boot_timestamp_duration_since(later.clone(), earlier.clone()).is_ok());
+
+        let other = BootTimestamp {
+            boot_id: earlier.boot_id.as_ref().map(|_| "0".repeat(32)),
+            boot_time: earlier.boot_time - 3600 * 1_000_000_000,
+            ..earlier
+        };
+        assert_eq!(
+            Err(TimestampError::DifferentBoot),
+            boot_timestamp_duration_since(later, other)
+        );
+    }
+}
@@ -0,0 +1,552 @@
+//! Blocking waits until a deadline on the suspend-including clock.
+//!
+//! The standard library's timed waits are measured on a clock that (depending on the operating
+//! system) does not advance while the machine is suspended.
+//! Waiting for a deadline from [`now`](crate::now) with them overshoots by the suspended time.
+//! Where possible we use a timer that is armed on the same clock instead.
+//! With the `forbid-unsafe` feature we always use the generic waiter.
+
+use std::convert::TryInto;
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+/// The longest a generic wait, or an async timer without a timerfd, sleeps before re-checking
+/// the clock.
+///
+/// This bounds how late a deadline fires after the system resumes from suspend.
+pub(crate) const MAX_SLICE: Duration = Duration::from_millis(250);
+
+thread_local! {
+    static WAITER: Waiter = Waiter::new();
+}
+
+/// Blocks the current thread until `deadline`, a timestamp as returned by [`now`](crate::now).
+///
+/// Time the system spends in suspend counts towards the deadline:
+/// a deadline that passed during suspend ends the sleep right after the system resumes.
+///
+/// On Linux and Android this waits on a `CLOCK_BOOTTIME` timer, on Windows on a
+/// high-resolution waitable timer and on macOS and iOS with a `dispatch_walltime` timeout.
+/// Elsewhere the sleep is split into slices of at most 250 ms,
+/// so a deadline passed during suspend is noticed within that time after resuming.
+pub fn sleep_until(deadline: u64) {
+    WAITER.with(|waiter| {
+        while crate::now() < deadline {
+            waiter.wait_until(Some(deadline));
+        }
+    })
+}
+
+/// Blocks the current thread for `duration`, including time the system spends in suspend.
+///
+/// See [`sleep_until`].
+pub fn sleep(duration: Duration) {
+    sleep_until(crate::now().saturating_add(as_nanos(duration)));
+}
+
+/// A duration in nanoseconds, saturating at `u64::MAX`, to add to a timestamp.
+pub(crate) fn as_nanos(duration: Duration) -> u64 {
+    duration.as_nanos().try_into().unwrap_or(u64::MAX)
+}
+
+/// Waits until a deadline passes or another thread wakes it up.
+pub(crate) enum Waiter {
+    #[cfg(all(
+        any(target_os = "linux", target_os = "android"),
+        not(feature = "forbid-unsafe")
+    ))]
+    TimerFd(timerfd::Waiter),
+    #[cfg(all(windows, not(feature = "forbid-unsafe")))]
+    WaitableTimer(waitable_timer::Waiter),
+    #[cfg(all(
+        any(target_os = "macos", target_os = "ios"),
+        not(feature = "forbid-unsafe")
+    ))]
+    Dispatch(dispatch::Waiter),
+    Generic(Generic),
+}
+
+impl Waiter {
+    pub(crate) fn new() -> Waiter {
+        #[cfg(all(
+            any(target_os = "linux", target_os = "android"),
+            not(feature = "forbid-unsafe")
+        ))]
+        {
+            if let Some(waiter) = timerfd::Waiter::new() {
+                return Waiter::TimerFd(waiter);
+            }
+        }
+        #[cfg(all(windows, not(feature = "forbid-unsafe")))]
+        {
+            if let Some(waiter) = waitable_timer::Waiter::new() {
+                return Waiter::WaitableTimer(waiter);
+            }
+        }
+        #[cfg(all(
+            any(target_os = "macos", target_os = "ios"),
+            not(feature = "forbid-unsafe")
+        ))]
+        {
+            if let Some(waiter) = dispatch::Waiter::new() {
+                return Waiter::Dispatch(waiter);
+            }
+        }
+
+        Waiter::Generic(Generic::new())
+    }
+
+    /// Blocks until `deadline` is reached, `wake` is called or a spurious wakeup occurs.
+    ///
+    /// Without a deadline it only returns when woken up.
+    /// Wakeups are not lost: a `wake` before the wait makes it return immediately.
+    pub(crate) fn wait_until(&self, deadline: Option<u64>) {
+        match self {
+            #[cfg(all(
+                any(target_os = "linux", target_os = "android"),
+                not(feature = "forbid-unsafe")
+            ))]
+            Waiter::TimerFd(waiter) => waiter.wait_until(deadline),
+            #[cfg(all(windows, not(feature = "forbid-unsafe")))]
+            Waiter::WaitableTimer(waiter) => waiter.wait_until(deadline),
+            #[cfg(all(
+                any(target_os = "macos", target_os = "ios"),
+                not(feature = "forbid-unsafe")
+            ))]
+            Waiter::Dispatch(waiter) => waiter.wait_until(deadline),
+            Waiter::Generic(waiter) => waiter.wait_until(deadline),
+        }
+    }
+
+    /// Wakes up a current or the next call to `wait_until`.
+    pub(crate) fn wake(&self) {
+        match self {
+            #[cfg(all(
+                any(target_os = "linux", target_os = "android"),
+                not(feature = "forbid-unsafe")
+            ))]
+            Waiter::TimerFd(waiter) => waiter.wake(),
+            #[cfg(all(windows, not(feature = "forbid-unsafe")))]
+            Waiter::WaitableTimer(waiter) => waiter.wake(),
+            #[cfg(all(
+                any(target_os = "macos", target_os = "ios"),
+                not(feature = "forbid-unsafe")
+            ))]
+            Waiter::Dispatch(waiter) => waiter.wake(),
+            Waiter::Generic(waiter) => waiter.wake(),
+        }
+    }
+}
+
+/// A waiter based on a condition variable, re-checking the clock at least every [`MAX_SLICE`].
+pub(crate) struct Generic {
+    woken: Mutex<bool>,
+    cond: Condvar,
+}
+
+impl Generic {
+    fn new() -> Generic {
+        Generic {
+            woken: Mutex::new(false),
+            cond: Condvar::new(),
+        }
+    }
+
+    fn wait_until(&self, deadline: Option<u64>) {
+        let mut woken = self.woken.lock().unwrap_or_else(|e| e.into_inner());
+        if !*woken {
+            let slice = match deadline {
+                Some(deadline) => {
+                    let remaining = Duration::from_nanos(deadline.saturating_sub(crate::now()));
+                    remaining.min(MAX_SLICE)
+                }
+                None => MAX_SLICE,
+            };
+            if slice > Duration::from_nanos(0) {
+                woken = self
+                    .cond
+                    .wait_timeout(woken, slice)
+                    .unwrap_or_else(|e| e.into_inner())
+                    .0;
+            }
+        }
+        *woken = false;
+    }
+
+    fn wake(&self) {
+        *self.woken.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        self.cond.notify_one();
+    }
+}
+
+#[cfg(all(
+    any(target_os = "linux", target_os = "android"),
+    not(feature = "forbid-unsafe")
+))]
+pub(crate) mod timerfd {
+    //! A waiter polling a `CLOCK_BOOTTIME` timerfd together with an eventfd for wakeups,
+    //! and the timerfd handling shared with the async timers.
+
+    const NS_PER_S: u64 = 1_000_000_000;
+
+    /// Creates a non-blocking `CLOCK_BOOTTIME` timerfd, `None` if there's no such clock.
+    pub(crate) fn create() -> Option<libc::c_int> {
+        let fd = unsafe {
+            libc::timerfd_create(libc::CLOCK_BOOTTIME, libc::TFD_CLOEXEC | libc::TFD_NONBLOCK)
+        };
+        if fd < 0 {
+            None
+        } else {
+            Some(fd)
+        }
+    }
+
+    /// Arms the timerfd `fd` to fire once at `deadline` on `CLOCK_BOOTTIME`,
+    /// or disarms it without a deadline. Returns whether that succeeded.
+    pub(crate) fn arm(fd: libc::c_int, deadline: Option<u64>) -> bool {
+        // An all-zero `it_value` disarms the timer, so a deadline of 0 becomes 1ns.
+        let deadline = deadline.map(|d| d.max(1)).unwrap_or(0);
+        let spec = libc::itimerspec {
+            it_interval: libc::timespec {
+                tv_sec: 0,
+                tv_nsec: 0,
+            },
+            it_value: libc::timespec {
+                tv_sec: (deadline / NS_PER_S) as libc::time_t,
+                tv_nsec: (deadline % NS_PER_S) as libc::c_long,
+            },
+        };
+        let ret = unsafe {
+            libc::timerfd_settime(fd, libc::TFD_TIMER_ABSTIME, &spec, std::ptr::null_mut())
+        };
+        ret == 0
+    }
+
+    pub(crate) struct Waiter {
+        timer: libc::c_int,
+        event: libc::c_int,
+    }
+
+    impl Waiter {
+        pub(crate) fn new() -> Option<Waiter> {
+            let timer = create()?;
+
+            let event = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+            if event < 0 {
+                unsafe { libc::close(timer) };
+                return None;
+            }
+
+            Some(Waiter { timer, event })
+        }
+
+        pub(crate) fn wait_until(&self, deadline: Option<u64>) {
+            arm(self.timer, deadline);
+
+            let mut fds = [
+                libc::pollfd {
+                    fd: self.timer,
+                    events: libc::POLLIN,
+                    revents: 0,
+                },
+                libc::pollfd {
+                    fd: self.event,
+                    events: libc::POLLIN,
+                    revents: 0,
+                },
+            ];
+
+            unsafe {
+                // Errors (e.g. `EINTR`) are fine: callers re-check their deadline anyway.
+                libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1);
+
+                // Drain both descriptors so they are not readable on the next wait.
+                let mut buf = 0u64;
+                for fd in &fds {
+                    if fd.revents & libc::POLLIN != 0 {
+                        libc::read(fd.fd, &mut buf as *mut u64 as *mut libc::c_void, 8);
+                    }
+                }
+            }
+        }
+
+        pub(crate) fn wake(&self) {
+            let one = 1u64;
+            unsafe {
+                libc::write(self.event, &one as *const u64 as *const libc::c_void, 8);
+            }
+        }
+    }
+
+    impl Drop for Waiter {
+        fn drop(&mut self) {
+            unsafe {
+                libc::close(self.timer);
+                libc::close(self.event);
+            }
+        }
+    }
+}
+
+#[cfg(all(windows, not(feature = "forbid-unsafe")))]
+mod waitable_timer {
+    //! A waiter on a high-resolution waitable timer together with an event for wakeups.
+    //!
+    //! The timer is set to an absolute due time in system time, which Windows honors across
+    //! sleep: a due time that passed while the system was suspended fires on resume.
+    //! Changes of the system time move the due time as well, so the wait is also bounded by
+    //! a timeout, which doesn't advance during suspend but does when the clock is set back.
+
+    use std::ffi::c_void;
+    use std::ptr;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// Seconds from 1601, the epoch of Windows system time, to 1970.
+    const EPOCH_DIFFERENCE: u64 = 11_644_473_600;
+    const CREATE_WAITABLE_TIMER_MANUAL_RESET: u32 = 0x1;
+    const CREATE_WAITABLE_TIMER_HIGH_RESOLUTION: u32 = 0x2;
+    const TIMER_ALL_ACCESS: u32 = 0x1F_0003;
+    const INFINITE: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateWaitableTimerExW(
+            attributes: *mut c_void,
+            name: *const u16,
+            flags: u32,
+            access: u32,
+        ) -> *mut c_void;
+        fn SetWaitableTimerEx(
+            timer: *mut c_void,
+            due_time: *const i64,
+            period: i32,
+            completion: *mut c_void,
+            arg: *mut c_void,
+            wake_context: *mut c_void,
+            tolerable_delay: u32,
+        ) -> i32;
+        fn CancelWaitableTimer(timer: *mut c_void) -> i32;
+        fn CreateEventW(
+            attributes: *mut c_void,
+            manual_reset: i32,
+            initial_state: i32,
+            name: *const u16,
+        ) -> *mut c_void;
+        fn SetEvent(event: *mut c_void) -> i32;
+        fn WaitForMultipleObjects(
+            count: u32,
+            handles: *const *mut c_void,
+            wait_all: i32,
+            millis: u32,
+        ) -> u32;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+    }
+
+    pub(crate) struct Waiter {
+        timer: *mut c_void,
+        event: *mut c_void,
+    }
+
+    // The handles can be used from any thread.
+    unsafe impl Send for Waiter {}
+    unsafe impl Sync for Waiter {}
+
+    impl Waiter {
+        pub(crate) fn new() -> Option<Waiter> {
+            let flags = CREATE_WAITABLE_TIMER_MANUAL_RESET;
+            let mut timer = unsafe {
+                CreateWaitableTimerExW(
+                    ptr::null_mut(),
+                    ptr::null(),
+                    flags | CREATE_WAITABLE_TIMER_HIGH_RESOLUTION,
+                    TIMER_ALL_ACCESS,
+                )
+            };
+            if timer.is_null() {
+                // High-resolution timers need Windows 10, version 1803.
+                timer = unsafe {
+                    CreateWaitableTimerExW(ptr::null_mut(), ptr::null(), flags, TIMER_ALL_ACCESS)
+                };
+            }
+            if timer.is_null() {
+                return None;
+            }
+
+            let event = unsafe { CreateEventW(ptr::null_mut(), 0, 0, ptr::null()) };
+            if event.is_null() {
+                unsafe { CloseHandle(timer) };
+                return None;
+            }
+
+            Some(Waiter { timer, event })
+        }
+
+        pub(crate) fn wait_until(&self, deadline: Option<u64>) {
+            let handles = [self.event, self.timer];
+            let (count, timeout) = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_sub(crate::now());
+                    let set = unsafe {
+                        SetWaitableTimerEx(
+                            self.timer,
+                            &due_time(remaining),
+                            0,
+                            ptr::null_mut(),
+                            ptr::null_mut(),
+                            ptr::null_mut(),
+                            0,
+                        )
+                    };
+                    // Round up, so waits end at or after the deadline.
+                    let millis = remaining.div_ceil(1_000_000);
+                    let timeout = millis.min(u64::from(INFINITE - 1)) as u32;
+                    if set == 0 {
+                        (1, timeout)
+                    } else {
+                        (2, timeout)
+                    }
+                }
+                None => (1, INFINITE),
+            };
+
+            // Errors are fine: callers re-check their deadline anyway.
+            unsafe {
+                WaitForMultipleObjects(count, handles.as_ptr(), 0, timeout);
+                CancelWaitableTimer(self.timer);
+            }
+        }
+
+        pub(crate) fn wake(&self) {
+            unsafe {
+                SetEvent(self.event);
+            }
+        }
+    }
+
+    /// The absolute due time `remaining` nanoseconds from now, in 100 ns units since 1601.
+    fn due_time(remaining: u64) -> i64 {
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_nanos() as u64)
+            .unwrap_or(0);
+        let due = since_epoch.saturating_add(remaining) / 100 + EPOCH_DIFFERENCE * 10_000_000;
+        due.min(i64::MAX as u64) as i64
+    }
+
+    impl Drop for Waiter {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.timer);
+                CloseHandle(self.event);
+            }
+        }
+    }
+}
+
+#[cfg(all(
+    any(target_os = "macos", target_os = "ios"),
+    not(feature = "forbid-unsafe")
+))]
+mod dispatch {
+    //! A waiter on a dispatch semaphore, with a timeout on the wall clock.
+    //!
+    //! Timeouts from `dispatch_time` are measured in Mach absolute time, which stops while the
+    //! system sleeps. Those from `dispatch_walltime` follow the wall clock, which keeps running,
+    //! so a deadline that passed during sleep ends the wait on wake.
+    //! Setting the system time back during a wait delays it by the same amount.
+
+    use std::ffi::c_void;
+    use std::ptr;
+
+    const DISPATCH_TIME_FOREVER: u64 = !0;
+
+    extern "C" {
+        fn dispatch_semaphore_create(value: isize) -> *mut c_void;
+        fn dispatch_semaphore_wait(semaphore: *mut c_void, timeout: u64) -> isize;
+        fn dispatch_semaphore_signal(semaphore: *mut c_void) -> isize;
+        fn dispatch_walltime(when: *const libc::timespec, delta: i64) -> u64;
+        fn dispatch_release(object: *mut c_void);
+    }
+
+    pub(crate) struct Waiter {
+        semaphore: *mut c_void,
+    }
+
+    // Dispatch objects are thread-safe.
+    unsafe impl Send for Waiter {}
+    unsafe impl Sync for Waiter {}
+
+    impl Waiter {
+        pub(crate) fn new() -> Option<Waiter> {
+            let semaphore = unsafe { dispatch_semaphore_create(0) };
+            if semaphore.is_null() {
+                return None;
+            }
+            Some(Waiter { semaphore })
+        }
+
+        pub(crate) fn wait_until(&self, deadline: Option<u64>) {
+            let timeout = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_sub(crate::now());
+                    unsafe { dispatch_walltime(ptr::null(), remaining.min(i64::MAX as u64) as i64) }
+                }
+                None => DISPATCH_TIME_FOREVER,
+            };
+            // A pending signal makes this return immediately, so wakeups are not lost.
+            // Several wakes before a wait lead to spurious wakeups, which callers handle.
+            unsafe {
+                dispatch_semaphore_wait(self.semaphore, timeout);
+            }
+        }
+
+        pub(crate) fn wake(&self) {
+            unsafe {
+                dispatch_semaphore_signal(self.semaphore);
+            }
+        }
+    }
+
+    impl Drop for Waiter {
+        fn drop(&mut self) {
+            unsafe { dispatch_release(self.semaphore) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sleeps_until_deadline() {
+        let deadline = crate::now() + 2_000_000;
+        sleep_until(deadline);
+        assert!(crate::now() >= deadline);
+
+        let start = crate::now();
+        sleep(Duration::from_millis(2));
+        assert!(crate::now() - start >= 2_000_000);
+    }
+
+    #[test]
+    fn waits_for_deadline() {
+        let waiter = Waiter::new();
+        let deadline = crate::now() + 2_000_000;
+        while crate::now() < deadline {
+            waiter.wait_until(Some(deadline));
+        }
+        assert!(crate::now() - deadline < 1_000_000_000);
+    }
+
+    #[test]
+    fn wakes_up() {
+        let waiter = std::sync::Arc::new(Waiter::new());
+        let other = waiter.clone();
+        let deadline = crate::now() + 60_000_000_000;
+        let thread = std::thread::spawn(move || other.wait_until(Some(deadline)));
+        waiter.wake();
+        thread.join().unwrap();
+        assert!(crate::now() < deadline);
+    }
+}
@@ -0,0 +1,68 @@
+//! Timestamps from the wall clock, for platforms without a monotonic suspend-aware clock.
+//!
+//! This backend is only used with the `wallclock-fallback` feature.
+//!
+//! The wall clock keeps running during suspend, but it can be changed at any time,
+//! by the user or by time synchronization.
+//! Backward jumps are clamped: timestamps stay at the latest returned value until the clock
+//! catches up again. Forward jumps can't be detected and appear as elapsed time.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::Error;
+use crate::guard::Latest;
+use crate::info::ClockInfo;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::Readings;
+
+/// The latest timestamp handed out.
+static LAST: Latest = Latest::new();
+
+/// The time since the Unix epoch, as reported by [`SystemTime`],
+/// never going backwards within a process.
+pub fn now_including_suspend() -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_nanos() as u64)
+        .unwrap_or(0);
+
+    LAST.advance(now)
+}
+
+/// The wall clock can't fail: times before the Unix epoch read as zero.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    Ok(now_including_suspend())
+}
+
+/// The wall clock can't tell suspend time apart,
+/// so this is the same clock as [`now_including_suspend`].
+pub fn now_excluding_suspend() -> u64 {
+    now_including_suspend()
+}
+
+/// There's a single clock, read once for both.
+pub fn now_both() -> Readings {
+    Readings::same(now_including_suspend())
+}
+
+/// The wall clock backend counts nanoseconds already.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: now_including_suspend(),
+        unit: TickUnit::Nanoseconds,
+    }
+}
+
+/// [`raw_now_including_suspend`] counts nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// The wall clock keeps running during suspend.
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        name: "SystemTime",
+        resolution: None,
+        includes_suspend: true,
+    }
+}
@@ -0,0 +1,60 @@
+//! Timestamp implementation for WASI, such as `wasm32-wasip1` and `wasm32-wasip2`.
+//!
+//! The monotonic clock is provided by the host runtime. It's nanosecond-precise where the host
+//! is, and comparable between all modules running on the same host, unlike the fallback that
+//! counts from the start of the module.
+//! Whether it includes time the host spends suspended depends on the runtime: Wasmtime and
+//! WasmEdge use their host's monotonic clock, which doesn't on Linux.
+
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::posix;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::Readings;
+
+/// The host's monotonic clock, or zero if it can't be read.
+///
+/// See [`clock_time_get`].
+///
+/// [`clock_time_get`]: https://github.com/WebAssembly/WASI/blob/main/legacy/preview1/docs.md#-clock_time_getid-clockid-precision-timestamp---result-timestamp-errno
+pub fn now_including_suspend() -> u64 {
+    try_now_including_suspend().unwrap_or(0)
+}
+
+/// Reports when the host doesn't provide the clock.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    posix::clock_gettime(libc::CLOCK_MONOTONIC)
+}
+
+/// WASI offers a single monotonic clock,
+/// so this is the same clock as [`now_including_suspend`].
+pub fn now_excluding_suspend() -> u64 {
+    now_including_suspend()
+}
+
+/// There's a single clock, read once for both.
+pub fn now_both() -> Readings {
+    Readings::same(now_including_suspend())
+}
+
+/// WASI counts nanoseconds already.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: now_including_suspend(),
+        unit: TickUnit::Nanoseconds,
+    }
+}
+
+/// [`raw_now_including_suspend`] counts nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// The host's clock, which can't be relied on to count suspend time.
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        name: "CLOCK_MONOTONIC",
+        resolution: posix::clock_getres(libc::CLOCK_MONOTONIC),
+        includes_suspend: false,
+    }
+}
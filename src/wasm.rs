@@ -0,0 +1,109 @@
+//! Timestamp implementation for WebAssembly in browsers, with the `wasm-js` feature.
+//!
+//! `performance.now()` is relative to the `performance.timeOrigin` of each context:
+//! the page and each of its Web Workers start counting at different times.
+//! Adding the time origin makes the timestamps of all of them directly comparable.
+//!
+//! See [`Performance`].
+//! Contexts without it, such as some embedded JavaScript engines, fall back to the wall clock
+//! from [`Date.now()`], at millisecond resolution and clamped to never go backwards.
+//!
+//! [`Performance`]: https://developer.mozilla.org/en-US/docs/Web/API/Performance
+//! [`Date.now()`]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/now
+
+use js_sys::Reflect;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::error::Error;
+use crate::guard::Latest;
+use crate::info::ClockInfo;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::Readings;
+
+const NS_PER_MS: f64 = 1_000_000.0;
+
+/// The latest timestamp from the `Date.now()` fallback.
+static LAST_DATE: Latest = Latest::new();
+
+#[wasm_bindgen]
+extern "C" {
+    type Performance;
+
+    #[wasm_bindgen(method)]
+    fn now(this: &Performance) -> f64;
+
+    #[wasm_bindgen(method, getter, js_name = timeOrigin)]
+    fn time_origin(this: &Performance) -> f64;
+}
+
+thread_local! {
+    /// The global `performance` object of the current context and its time origin.
+    static PERFORMANCE: Option<(Performance, f64)> = {
+        Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+            .ok()
+            .filter(|performance| performance.is_object())
+            .map(|performance| {
+                let performance: Performance = performance.unchecked_into();
+                let origin = performance.time_origin();
+                (performance, origin)
+            })
+    };
+}
+
+/// The time from the browser's monotonic clock, in nanoseconds since the Unix epoch at which
+/// the page's time origin was established.
+///
+/// Whether it includes time the system spends suspended depends on the browser.
+/// Without `performance`, this is the wall clock instead.
+pub fn now_including_suspend() -> u64 {
+    PERFORMANCE.with(|performance| match performance {
+        Some((performance, origin)) => ((origin + performance.now()) * NS_PER_MS) as u64,
+        None => LAST_DATE.advance((js_sys::Date::now() * NS_PER_MS) as u64),
+    })
+}
+
+/// Neither `performance.now()` nor `Date.now()` can fail.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    Ok(now_including_suspend())
+}
+
+/// The browser offers a single monotonic clock,
+/// so this is the same clock as [`now_including_suspend`].
+pub fn now_excluding_suspend() -> u64 {
+    now_including_suspend()
+}
+
+/// There's a single clock, read once for both.
+pub fn now_both() -> Readings {
+    Readings::same(now_including_suspend())
+}
+
+/// The conversion from fractional milliseconds to nanoseconds is lossless enough,
+/// so this counts nanoseconds as well.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    RawTimestamp {
+        value: now_including_suspend(),
+        unit: TickUnit::Nanoseconds,
+    }
+}
+
+/// [`raw_now_including_suspend`] counts nanoseconds.
+pub fn tick_unit() -> TickUnit {
+    TickUnit::Nanoseconds
+}
+
+/// Browsers coarsen `performance.now()` against timing attacks, without reporting by how much,
+/// and not all of them count suspend time.
+pub fn clock_info() -> ClockInfo {
+    let performance = PERFORMANCE.with(|performance| performance.is_some());
+    ClockInfo {
+        name: if performance {
+            "performance.now()"
+        } else {
+            "Date.now()"
+        },
+        resolution: None,
+        includes_suspend: false,
+    }
+}
@@ -1,42 +1,318 @@
-//! Timestamp implementation for Windows 10+ or Windows Server 2016+.
+//! Timestamp implementation for Windows 7+ or Windows Server 2008 R2+.
 //!
-//! Lower versions don't have the necessary API and should use the fallback.
+//! `QueryInterruptTime`, available since Windows 10 and Windows Server 2016,
+//! is looked up at runtime.
+//! Where it's missing, as on Windows 7 and 8, or stubbed out, as under some versions of Wine or
+//! ReactOS, we fall back to `GetTickCount64`, which also includes suspend time,
+//! but only has millisecond resolution.
+//!
+//! With the `kuser-shared-data` feature the interrupt time is read straight from the
+//! `KUSER_SHARED_DATA` page the kernel maps into every process, which is what
+//! `QueryInterruptTime` does too, without the function call.
+//!
+//! Both only advance with the timer interrupt, every 15.6 milliseconds unless a process raised
+//! the timer resolution. With the `precise-interrupt-time` feature `QueryInterruptTimePrecise`
+//! and `QueryUnbiasedInterruptTimePrecise` are used where available instead, which also read
+//! the performance counter to interpolate. They take precedence over `kuser-shared-data`.
+
+use std::ffi::c_void;
+#[cfg(feature = "precise-interrupt-time")]
+use std::time::Duration;
 
-#![cfg(feature = "win10plus")]
+use once_cell::sync::Lazy;
 
-/// [PULONGLONG] is a pointer to [ULONGLONG], a 64-bit unsigned integer.
+use crate::error::Error;
+use crate::info::ClockInfo;
+use crate::raw::{RawTimestamp, TickUnit};
+use crate::readings::{bracketed, Readings};
+
+/// A pointer to a 64-bit unsigned integer, see [PULONGLONG].
 ///
 /// [PULONGLONG]: https://docs.microsoft.com/en-us/windows/win32/winprog/windows-data-types#PULONGLONG
-/// [ULONGLONG]: https://docs.microsoft.com/en-us/windows/win32/winprog/windows-data-types#ulonglong
-type PULONGLONG = *mut u64;
+type PUlongLong = *mut u64;
+
+/// Gets the current interrupt-time count.
+///
+/// See [`QueryInterruptTime`].
+///
+/// [`QueryInterruptTime`]: https://docs.microsoft.com/en-us/windows/win32/api/realtimeapiset/nf-realtimeapiset-queryinterrupttime
+type QueryInterruptTimeFn = unsafe extern "system" fn(interrupt_time: PUlongLong);
 
-/// Link against Windows' `mincore`.
-#[link(name = "mincore")]
+#[link(name = "kernel32")]
 extern "system" {
-    /// Gets the current interrupt-time count.
+    fn GetModuleHandleW(module_name: *const u16) -> *mut c_void;
+    fn LoadLibraryW(file_name: *const u16) -> *mut c_void;
+    fn GetProcAddress(module: *mut c_void, proc_name: *const u8) -> *mut c_void;
+
+    /// Gets the number of milliseconds since the system was started.
+    ///
+    /// See [`GetTickCount64`].
     ///
-    /// See [`QueryInterruptTime`].
+    /// [`GetTickCount64`]: https://docs.microsoft.com/en-us/windows/win32/api/sysinfoapi/nf-sysinfoapi-gettickcount64
+    fn GetTickCount64() -> u64;
+
+    /// Gets the current unbiased interrupt-time count.
     ///
-    /// [`QueryInterruptTime`]: https://docs.microsoft.com/en-us/windows/win32/api/realtimeapiset/nf-realtimeapiset-queryinterrupttime
+    /// See [`QueryUnbiasedInterruptTime`].
     ///
-    /// Note: we define it ourselves, because it's not actually included in `winapi`.
-    fn QueryInterruptTime(InterruptTime: PULONGLONG);
+    /// [`QueryUnbiasedInterruptTime`]: https://docs.microsoft.com/en-us/windows/win32/api/realtimeapiset/nf-realtimeapiset-queryunbiasedinterrupttime
+    fn QueryUnbiasedInterruptTime(unbiased_time: PUlongLong) -> i32;
 }
 
 /// Windows counts time in a system time unit of 100 nanoseconds.
 const SYSTEM_TIME_UNIT: u64 = 100;
 
+/// `GetTickCount64` counts milliseconds.
+const NS_PER_MS: u64 = 1_000_000;
+
+/// Where the suspend-including time comes from.
+#[derive(Clone, Copy)]
+enum Source {
+    #[cfg(feature = "precise-interrupt-time")]
+    InterruptTimePrecise(QueryInterruptTimeFn),
+    #[cfg(feature = "kuser-shared-data")]
+    SharedData,
+    InterruptTime(QueryInterruptTimeFn),
+    TickCount,
+}
+
+static SOURCE: Lazy<Source> = Lazy::new(|| {
+    #[cfg(feature = "precise-interrupt-time")]
+    {
+        if let Some(query) = find(b"QueryInterruptTimePrecise\0").filter(|&query| works(query)) {
+            return Source::InterruptTimePrecise(query);
+        }
+    }
+
+    #[cfg(feature = "kuser-shared-data")]
+    {
+        if shared_interrupt_time() != 0 {
+            return Source::SharedData;
+        }
+    }
+
+    match find(b"QueryInterruptTime\0") {
+        Some(query) if works(query) => Source::InterruptTime(query),
+        _ => Source::TickCount,
+    }
+});
+
+/// `QueryUnbiasedInterruptTimePrecise`, where available.
+#[cfg(feature = "precise-interrupt-time")]
+static UNBIASED_PRECISE: Lazy<Option<QueryInterruptTimeFn>> =
+    Lazy::new(|| find(b"QueryUnbiasedInterruptTimePrecise\0").filter(|&query| works(query)));
+
+/// The fixed user-mode address of `KUSER_SHARED_DATA`.
+#[cfg(feature = "kuser-shared-data")]
+const KUSER_SHARED_DATA: usize = 0x7ffe_0000;
+
+/// The interrupt time in `KUSER_SHARED_DATA`, a `KSYSTEM_TIME` at offset 8:
+/// the low 32 bits, followed by two copies of the high 32 bits.
+///
+/// The kernel writes the second high part first, then the low part, then the first high
+/// part, so reading them in the opposite order and comparing both high parts detects a
+/// torn read.
+#[cfg(feature = "kuser-shared-data")]
+fn shared_interrupt_time() -> u64 {
+    let base = KUSER_SHARED_DATA as *const u32;
+    loop {
+        let (high1, low, high2) = unsafe {
+            (
+                std::ptr::read_volatile(base.add(3)),
+                std::ptr::read_volatile(base.add(2)),
+                std::ptr::read_volatile(base.add(4)),
+            )
+        };
+        if high1 == high2 {
+            return u64::from(high1) << 32 | u64::from(low);
+        }
+        std::hint::spin_loop();
+    }
+}
+
+/// Looks up one of the `Query*InterruptTime*` functions, all taking a pointer to the count,
+/// in the already loaded `kernelbase.dll` or its API sets.
+///
+/// `name` must be nul-terminated.
+fn find(name: &[u8]) -> Option<QueryInterruptTimeFn> {
+    for dll in &[
+        "kernelbase.dll",
+        "api-ms-win-core-realtime-l1-1-1.dll",
+        "api-ms-win-core-realtime-l1-1-2.dll",
+    ] {
+        let wide: Vec<u16> = dll.encode_utf16().chain(Some(0)).collect();
+        unsafe {
+            let mut module = GetModuleHandleW(wide.as_ptr());
+            if module.is_null() {
+                module = LoadLibraryW(wide.as_ptr());
+            }
+            if module.is_null() {
+                continue;
+            }
+
+            let query = GetProcAddress(module, name.as_ptr());
+            if !query.is_null() {
+                return Some(std::mem::transmute::<*mut c_void, QueryInterruptTimeFn>(
+                    query,
+                ));
+            }
+        }
+    }
+
+    None
+}
+
+/// Stubbed implementations leave the count at zero, which it never is on a running system.
+fn works(query: QueryInterruptTimeFn) -> bool {
+    let mut interrupt_time = 0;
+    unsafe {
+        query(&mut interrupt_time);
+    }
+    interrupt_time != 0
+}
+
 /// The time based on the current interrupt-time count.
 /// This includes the suspend time.
 ///
 /// See [`QueryInterruptTime`].
+/// Falls back to [`GetTickCount64`] if that's not available.
 ///
 /// [`QueryInterruptTime`]: https://docs.microsoft.com/en-us/windows/win32/api/realtimeapiset/nf-realtimeapiset-queryinterrupttime
+/// [`GetTickCount64`]: https://docs.microsoft.com/en-us/windows/win32/api/sysinfoapi/nf-sysinfoapi-gettickcount64
 pub fn now_including_suspend() -> u64 {
+    raw_now_including_suspend().to_nanos()
+}
+
+/// Missing `QueryInterruptTime` already falls back to `GetTickCount64`, reading never fails.
+pub fn try_now_including_suspend() -> Result<u64, Error> {
+    Ok(now_including_suspend())
+}
+
+/// The time based on the current unbiased interrupt-time count.
+/// This does not include the suspend time.
+///
+/// See [`QueryUnbiasedInterruptTime`].
+///
+/// [`QueryUnbiasedInterruptTime`]: https://docs.microsoft.com/en-us/windows/win32/api/realtimeapiset/nf-realtimeapiset-queryunbiasedinterrupttime
+pub fn now_excluding_suspend() -> u64 {
+    let mut unbiased_time = 0;
+    #[cfg(feature = "precise-interrupt-time")]
+    {
+        if let Some(query) = *UNBIASED_PRECISE {
+            unsafe {
+                query(&mut unbiased_time);
+            }
+            return unbiased_time * SYSTEM_TIME_UNIT;
+        }
+    }
+
+    unsafe {
+        QueryUnbiasedInterruptTime(&mut unbiased_time);
+    }
+
+    unbiased_time * SYSTEM_TIME_UNIT
+}
+
+/// Reads both clocks back-to-back.
+pub fn now_both() -> Readings {
+    bracketed(now_including_suspend, now_excluding_suspend)
+}
+
+/// The count backing [`now_including_suspend`]:
+/// system time units of the interrupt time, or milliseconds of the tick count.
+pub fn raw_now_including_suspend() -> RawTimestamp {
+    match *SOURCE {
+        #[cfg(feature = "precise-interrupt-time")]
+        Source::InterruptTimePrecise(query) => interrupt_time(query),
+        #[cfg(feature = "kuser-shared-data")]
+        Source::SharedData => RawTimestamp {
+            value: shared_interrupt_time(),
+            unit: TickUnit::HundredNanoseconds,
+        },
+        Source::InterruptTime(query) => interrupt_time(query),
+        Source::TickCount => RawTimestamp {
+            value: unsafe { GetTickCount64() },
+            unit: tick_unit(),
+        },
+    }
+}
+
+/// The unit of [`raw_now_including_suspend`] for the clock that was picked.
+pub fn tick_unit() -> TickUnit {
+    match *SOURCE {
+        Source::TickCount => TickUnit::Timebase {
+            numer: NS_PER_MS as u32,
+            denom: 1,
+        },
+        _ => TickUnit::HundredNanoseconds,
+    }
+}
+
+/// `QueryInterruptTime`, for [`coarse_now_including_suspend`] next to the precise interrupt time.
+#[cfg(all(feature = "coarse", feature = "precise-interrupt-time"))]
+static INTERRUPT_TIME: Lazy<Option<QueryInterruptTimeFn>> =
+    Lazy::new(|| find(b"QueryInterruptTime\0").filter(|&query| works(query)));
+
+/// The same clock as [`now_including_suspend`], without interpolating between timer interrupts.
+///
+/// Only the precise interrupt time interpolates. Next to it this reads the interrupt time from
+/// `KUSER_SHARED_DATA` with the `kuser-shared-data` feature, or with `QueryInterruptTime`.
+#[cfg(feature = "coarse")]
+pub(crate) fn coarse_now_including_suspend() -> u64 {
+    #[cfg(feature = "precise-interrupt-time")]
+    {
+        if let Source::InterruptTimePrecise(_) = *SOURCE {
+            #[cfg(feature = "kuser-shared-data")]
+            {
+                let shared = shared_interrupt_time();
+                if shared != 0 {
+                    return shared * SYSTEM_TIME_UNIT;
+                }
+            }
+
+            if let Some(query) = *INTERRUPT_TIME {
+                return interrupt_time(query).to_nanos();
+            }
+        }
+    }
+
+    now_including_suspend()
+}
+
+fn interrupt_time(query: QueryInterruptTimeFn) -> RawTimestamp {
     let mut interrupt_time = 0;
     unsafe {
-        QueryInterruptTime(&mut interrupt_time);
+        query(&mut interrupt_time);
+    }
+
+    RawTimestamp {
+        value: interrupt_time,
+        unit: TickUnit::HundredNanoseconds,
     }
+}
 
-    interrupt_time * SYSTEM_TIME_UNIT
+/// The interrupt time and the tick count both advance with the timer interrupt,
+/// so the resolution is the current timer resolution.
+///
+/// The precise interrupt time is interpolated down to its unit of 100 nanoseconds.
+pub fn clock_info() -> ClockInfo {
+    let name = match *SOURCE {
+        #[cfg(feature = "precise-interrupt-time")]
+        Source::InterruptTimePrecise(_) => {
+            return ClockInfo {
+                name: "QueryInterruptTimePrecise",
+                resolution: Some(Duration::from_nanos(SYSTEM_TIME_UNIT)),
+                includes_suspend: true,
+            }
+        }
+        #[cfg(feature = "kuser-shared-data")]
+        Source::SharedData => "KUSER_SHARED_DATA",
+        Source::InterruptTime(_) => "QueryInterruptTime",
+        Source::TickCount => "GetTickCount64",
+    };
+    ClockInfo {
+        name,
+        resolution: crate::timer_resolution::current().map(|resolution| resolution.current),
+        includes_suspend: true,
+    }
 }
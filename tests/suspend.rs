@@ -0,0 +1,134 @@
+//! Verifies the crate's core claim: `now()` advances while the system is suspended,
+//! and `now_excluding_suspend()` doesn't.
+//!
+//! This actually suspends the machine, so it's ignored by default and needs privileges.
+//! Run it with:
+//!
+//! ```text
+//! ZEITSTEMPEL_SUSPEND_TEST=1 sudo -E cargo test --test suspend -- --ignored
+//! ```
+//!
+//! * Linux: `rtcwake -m mem` suspends to RAM and wakes up through the RTC alarm.
+//! * macOS: `pmset relative wake` schedules the wakeup, `pmset sleepnow` sleeps.
+//! * Windows: a waitable timer with resume wakes up from `SetSuspendState`.
+//!   Wake timers must be allowed in the power options.
+
+#![cfg(any(target_os = "linux", target_os = "macos", windows))]
+
+use std::env;
+use std::time::Duration;
+
+/// How long the system stays suspended, in seconds.
+const SUSPEND_SECS: u64 = 10;
+
+#[cfg(target_os = "linux")]
+fn suspend(secs: u64) {
+    use std::process::Command;
+
+    let status = Command::new("rtcwake")
+        .args(["-m", "mem", "-s", &secs.to_string()])
+        .status()
+        .expect("failed to run rtcwake");
+    assert!(status.success(), "rtcwake failed: {}", status);
+}
+
+#[cfg(target_os = "macos")]
+fn suspend(secs: u64) {
+    use std::process::Command;
+    use std::thread;
+
+    let status = Command::new("pmset")
+        .args(["relative", "wake", &secs.to_string()])
+        .status()
+        .expect("failed to run pmset");
+    assert!(status.success(), "scheduling the wakeup failed: {}", status);
+
+    let status = Command::new("pmset")
+        .arg("sleepnow")
+        .status()
+        .expect("failed to run pmset");
+    assert!(status.success(), "pmset sleepnow failed: {}", status);
+
+    // `sleepnow` returns before the system actually sleeps.
+    // Thread sleeps don't advance during suspend, so this ends only after the wakeup.
+    thread::sleep(Duration::from_secs(5));
+}
+
+#[cfg(windows)]
+fn suspend(secs: u64) {
+    use std::ffi::c_void;
+    use std::ptr;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateWaitableTimerW(
+            attributes: *mut c_void,
+            manual_reset: i32,
+            name: *const u16,
+        ) -> *mut c_void;
+        fn SetWaitableTimer(
+            timer: *mut c_void,
+            due_time: *const i64,
+            period: i32,
+            completion: *mut c_void,
+            arg: *mut c_void,
+            resume: i32,
+        ) -> i32;
+        fn WaitForSingleObject(handle: *mut c_void, millis: u32) -> u32;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+    }
+
+    #[link(name = "powrprof")]
+    extern "system" {
+        fn SetSuspendState(hibernate: u8, force: u8, wakeup_events_disabled: u8) -> u8;
+    }
+
+    const INFINITE: u32 = u32::MAX;
+
+    unsafe {
+        let timer = CreateWaitableTimerW(ptr::null_mut(), 1, ptr::null());
+        assert!(!timer.is_null(), "CreateWaitableTimerW failed");
+
+        // Negative due times are relative, in 100 ns units.
+        let due_time = -(secs as i64) * 10_000_000;
+        let set = SetWaitableTimer(timer, &due_time, 0, ptr::null_mut(), ptr::null_mut(), 1);
+        assert!(set != 0, "SetWaitableTimer failed");
+
+        assert!(SetSuspendState(0, 0, 0) != 0, "SetSuspendState failed");
+        WaitForSingleObject(timer, INFINITE);
+        CloseHandle(timer);
+    }
+}
+
+#[test]
+#[ignore]
+fn now_advances_during_suspend() {
+    if env::var_os("ZEITSTEMPEL_SUSPEND_TEST").is_none() {
+        eprintln!("ZEITSTEMPEL_SUSPEND_TEST is not set, not suspending the system");
+        return;
+    }
+
+    if cfg!(all(windows, feature = "forbid-unsafe")) {
+        eprintln!("The fallback clock doesn't tell suspend time apart, disable `forbid-unsafe`");
+        return;
+    }
+
+    let including_start = zeitstempel::now();
+    let excluding_start = zeitstempel::now_excluding_suspend();
+
+    suspend(SUSPEND_SECS);
+
+    let including = Duration::from_nanos(zeitstempel::now() - including_start);
+    let excluding = Duration::from_nanos(zeitstempel::now_excluding_suspend() - excluding_start);
+
+    // Entering and leaving suspend takes a while, during which both clocks run.
+    // Only the suspended period itself has to be missing from the excluding clock.
+    let suspended = including.saturating_sub(excluding);
+    assert!(
+        suspended >= Duration::from_secs(SUSPEND_SECS / 2),
+        "expected about {}s of suspend, measured {:?} including and {:?} excluding suspend",
+        SUSPEND_SECS,
+        including,
+        excluding
+    );
+}
@@ -0,0 +1,38 @@
+//! Drives the virtual clock of the `testing` feature.
+//!
+//! It's shared by the whole process, so it's tested in its own binary, in a single test.
+//!
+//! ```text
+//! cargo test --features testing --test testing
+//! ```
+
+#![cfg(feature = "testing")]
+
+use std::time::Duration;
+
+use zeitstempel::{test, Instant, Timestamp};
+
+#[test]
+fn virtual_clock() {
+    test::freeze();
+    test::set_time(1_000_000_000);
+    assert_eq!(1_000_000_000, zeitstempel::now());
+    assert_eq!(1_000, zeitstempel::now_ms());
+
+    let start = Instant::now();
+    let excluding = zeitstempel::now_excluding_suspend();
+    test::advance(Duration::from_secs(5));
+    assert_eq!(Duration::from_secs(5), start.elapsed());
+    assert_eq!(
+        excluding + 5_000_000_000,
+        zeitstempel::now_excluding_suspend()
+    );
+    assert_eq!(Timestamp::from(6_000_000_000), Timestamp::now());
+
+    test::unfreeze();
+    let running = zeitstempel::now();
+    assert!(running >= 6_000_000_000);
+    assert!(zeitstempel::now() >= running);
+
+    test::reset();
+}
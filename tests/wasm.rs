@@ -0,0 +1,24 @@
+#![cfg(all(target_arch = "wasm32", target_os = "unknown", feature = "wasm-js"))]
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn increases() {
+    let ts1 = zeitstempel::now();
+    let ts2 = zeitstempel::now();
+
+    assert!(ts1 > 0);
+    assert!(ts1 <= ts2);
+}
+
+/// Timestamps are normalized to the time origin, so they're close to the wall clock,
+/// no matter which context (page or worker) takes them.
+#[wasm_bindgen_test]
+fn normalized_to_time_origin() {
+    let ms = zeitstempel::now() as f64 / 1_000_000.0;
+    let wall_clock = js_sys::Date::now();
+
+    assert!((ms - wall_clock).abs() < 60_000.0);
+}
@@ -0,0 +1,70 @@
+//! Procedural macros for [zeitstempel].
+//!
+//! Use them through zeitstempel's `macros` feature, not by depending on this crate directly.
+//!
+//! [zeitstempel]: https://docs.rs/zeitstempel
+
+#![deny(missing_docs)]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, ItemFn, LitStr, Path};
+
+/// Measures how long a function takes, including time the system spends in suspend.
+///
+/// The duration is reported to a sink when the function returns, unwinds or,
+/// for an `async fn`, when its future completes or is dropped.
+///
+/// Without arguments it reports to the global sink set with `zeitstempel::timed::set_sink`,
+/// under the name `module::path::function`.
+///
+/// * `name = "..."` reports under a custom name.
+/// * `sink = path::to::function` reports to a function `fn(&'static str, Duration)` instead.
+///
+/// ```ignore
+/// #[zeitstempel::timed(name = "upload", sink = report)]
+/// async fn upload(data: &[u8]) { /* ... */ }
+///
+/// fn report(name: &'static str, elapsed: std::time::Duration) {
+///     log::info!("{} took {:?}", name, elapsed);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn timed(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut name: Option<LitStr> = None;
+    let mut sink: Option<Path> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("sink") {
+            sink = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported property, expected `name` or `sink`"))
+        }
+    });
+    parse_macro_input!(attr with parser);
+
+    let mut function = parse_macro_input!(item as ItemFn);
+
+    let ident = &function.sig.ident;
+    let name = match name {
+        Some(name) => quote!(#name),
+        None => quote!(concat!(module_path!(), "::", stringify!(#ident))),
+    };
+    let sink = match sink {
+        Some(sink) => quote!(::core::option::Option::Some(#sink)),
+        None => quote!(::core::option::Option::None),
+    };
+
+    let body = &function.block;
+    function.block = parse_quote!({
+        let __zeitstempel_guard = ::zeitstempel::timed::Guard::new(#name, #sink);
+        #body
+    });
+
+    quote!(#function).into()
+}
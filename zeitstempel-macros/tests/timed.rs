@@ -0,0 +1,81 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+
+static REPORTS: Mutex<Vec<(&'static str, Duration)>> = Mutex::new(Vec::new());
+
+fn record(name: &'static str, elapsed: Duration) {
+    REPORTS.lock().unwrap().push((name, elapsed));
+}
+
+fn reports(name: &str) -> Vec<Duration> {
+    REPORTS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(n, _)| *n == name)
+        .map(|(_, d)| *d)
+        .collect()
+}
+
+#[zeitstempel::timed(sink = record)]
+fn sleepy(ms: u64) -> u64 {
+    thread::sleep(Duration::from_millis(ms));
+    ms
+}
+
+#[zeitstempel::timed(name = "early", sink = record)]
+fn early_return(bail: bool) -> Option<u32> {
+    if bail {
+        return None;
+    }
+    Some(1)
+}
+
+#[zeitstempel::timed(name = "async", sink = record)]
+async fn sleepy_async() -> &'static str {
+    thread::sleep(Duration::from_millis(2));
+    "done"
+}
+
+#[test]
+fn reports_duration() {
+    assert_eq!(2, sleepy(2));
+
+    let name = concat!(module_path!(), "::sleepy");
+    let reported = reports(name);
+    assert_eq!(1, reported.len());
+    assert!(reported[0] >= Duration::from_millis(2));
+}
+
+#[test]
+fn reports_early_returns() {
+    assert_eq!(None, early_return(true));
+    assert_eq!(Some(1), early_return(false));
+    assert_eq!(2, reports("early").len());
+}
+
+/// Wakes nobody: the future under test never returns `Poll::Pending`.
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+#[test]
+fn reports_async_on_completion() {
+    let mut future = Box::pin(sleepy_async());
+    assert!(reports("async").is_empty());
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    let poll = Pin::as_mut(&mut future).poll(&mut cx);
+    assert_eq!(Poll::Ready("done"), poll);
+
+    let reported = reports("async");
+    assert_eq!(1, reported.len());
+    assert!(reported[0] >= Duration::from_millis(2));
+}